@@ -0,0 +1,338 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::format::CommandRunner;
+use crate::commands::parse_preset;
+use crate::console::Console;
+use crate::diagnostics::CliDiagnostic;
+use crate::origin::{merge_layers, ConfigurationLayer, Origin};
+use crate::resolver::ConfigurationResolver;
+use crate::rule_overrides::RuleOverrides;
+use crate::workspace::Workspace;
+use serde_json::{Map, Value};
+use simora_configuration::{PartialMarkdownFormatterConfiguration, Preset};
+use simora_formatter::MarkdownFormatter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigAction {
+    /// Print the resolved configuration as JSON
+    Print,
+    /// Print every resolved value with where it was set
+    Explain,
+    Help,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigCommand {
+    pub action: ConfigAction,
+    /// File to resolve the configuration for, including matching overrides
+    pub file: Option<PathBuf>,
+    pub preset: Option<&'static Preset>,
+    pub rule_overrides: RuleOverrides,
+}
+
+impl ConfigCommand {
+    /// Parses the arguments following `ripari config`
+    pub fn from_args(args: &[String]) -> Result<Self, CliDiagnostic> {
+        let action = match args.first().map(String::as_str) {
+            Some("print") => ConfigAction::Print,
+            Some("explain") => ConfigAction::Explain,
+            Some("--help" | "-h") | None => ConfigAction::Help,
+            Some(other) => {
+                return Err(CliDiagnostic::error(format!(
+                    "Unknown config command `{}`. Expected `print` or `explain`.",
+                    other
+                )))
+            }
+        };
+        let mut command = Self {
+            action,
+            file: None,
+            preset: None,
+            rule_overrides: RuleOverrides::from_env(std::env::vars())?,
+        };
+
+        let mut i = 1;
+        while i < args.len() {
+            if command.rule_overrides.parse_flag(args, &mut i)? {
+                i += 1;
+                continue;
+            }
+            match args[i].as_str() {
+                "--preset" => {
+                    if i + 1 < args.len() {
+                        command.preset = Some(parse_preset(&args[i + 1])?);
+                        i += 1;
+                    }
+                }
+                arg if arg.starts_with("--preset=") => {
+                    command.preset = Some(parse_preset(&arg["--preset=".len()..])?);
+                }
+                "--help" | "-h" => command.action = ConfigAction::Help,
+                arg if arg.starts_with("--") => {
+                    return Err(CliDiagnostic::error(format!("Unknown flag `{}`", arg)));
+                }
+                arg => command.file = Some(PathBuf::from(arg)),
+            }
+            i += 1;
+        }
+        Ok(command)
+    }
+
+    /// The layers that apply to the requested file, or to the workspace root
+    fn layers(
+        &self,
+        console: &impl Console,
+        resolver: &mut ConfigurationResolver,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        let mut layers = vec![ConfigurationLayer {
+            origin: Origin::Default,
+            configuration: PartialMarkdownFormatterConfiguration {
+                markdown: Some(MarkdownFormatter::default_config()),
+                ..Default::default()
+            },
+        }];
+        layers.extend(match &self.file {
+            // Existing documents also bring their front matter
+            Some(file) => match fs::read_to_string(file) {
+                Ok(content) => resolver.document_layers(file, &content, console)?,
+                Err(_) => resolver.file_layers(file, console)?,
+            },
+            None => {
+                let root = resolver.root().to_path_buf();
+                resolver.directory_layers(&root, console)?
+            }
+        });
+        Ok(layers)
+    }
+
+    fn print_help(console: &impl Console) {
+        console.log("Show the configuration that applies to a file.");
+        console.log("");
+        console.log("Usage: ripari config <print|explain> [FILE]");
+        console.log("");
+        console.log("Commands:");
+        console.log("    print                    Print the resolved configuration as JSON");
+        console.log("    explain                  Show where each resolved value was set");
+        console.log("");
+        console.log("Available positional items:");
+        console.log("    FILE                     File whose overrides are applied");
+        console.log("");
+        console.log("Available options:");
+        console.log("        --preset=NAME         Apply a built-in preset");
+        console.log("        --rule, --only, --skip  Change rules as for `ripari format`");
+        console.log("    -h, --help               Prints help information");
+    }
+}
+
+impl CommandRunner for ConfigCommand {
+    fn execute(&self, console: &impl Console, workspace: &Workspace) -> Result<(), CliDiagnostic> {
+        if self.action == ConfigAction::Help {
+            Self::print_help(console);
+            return Ok(());
+        }
+
+        let mut resolver = ConfigurationResolver::new(workspace.root())
+            .with_preset(self.preset)
+            .with_rule_overrides(self.rule_overrides.clone());
+        let layers = self.layers(console, &mut resolver)?;
+        let resolved = to_value(&merge_layers(&layers));
+
+        if self.action == ConfigAction::Print {
+            let json = serde_json::to_string_pretty(&resolved).map_err(|e| {
+                CliDiagnostic::error(format!("Failed to serialize configuration: {}", e))
+            })?;
+            console.log(&json);
+            return Ok(());
+        }
+
+        let layer_values: Vec<Vec<(String, Value)>> = layers
+            .iter()
+            .map(|layer| leaves(&to_value(&layer.configuration)))
+            .collect();
+        for (key, value) in leaves(&resolved) {
+            // Arrays of overrides are not applied without a file, there is nothing to explain
+            if key == "overrides" {
+                continue;
+            }
+            let origin = layers
+                .iter()
+                .zip(&layer_values)
+                .rev()
+                .find(|(_, values)| values.iter().any(|(candidate, _)| *candidate == key))
+                .map(|(layer, _)| layer.origin.describe(&key, resolver.root()))
+                .unwrap_or_default();
+            console.log(&format!("{} = {}  # {}", key, value, origin));
+        }
+        Ok(())
+    }
+}
+
+/// Serializes a configuration without unset values or the `root` marker
+fn to_value(configuration: &PartialMarkdownFormatterConfiguration) -> Value {
+    let mut value = serde_json::to_value(configuration).unwrap_or_default();
+    if let Some(members) = value.as_object_mut() {
+        members.remove("root");
+    }
+    remove_nulls(&mut value);
+    value
+}
+
+fn remove_nulls(value: &mut Value) {
+    if let Some(members) = value.as_object_mut() {
+        members.retain(|_, member| !member.is_null());
+        members.values_mut().for_each(remove_nulls);
+    }
+}
+
+/// Flattens nested objects into `(json.path, value)` pairs, arrays are kept whole
+fn leaves(value: &Value) -> Vec<(String, Value)> {
+    fn collect(prefix: &str, members: &Map<String, Value>, out: &mut Vec<(String, Value)>) {
+        for (key, value) in members {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Object(members) => collect(&path, members, out),
+                value => out.push((path, value.clone())),
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    if let Some(members) = value.as_object() {
+        collect("", members, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{temp_dir, MockConsole};
+    use serde_json::json;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let command =
+            ConfigCommand::from_args(&args(&["explain", "docs/a.md", "--preset=gemini"])).unwrap();
+        assert_eq!(command.action, ConfigAction::Explain);
+        assert_eq!(command.file, Some(PathBuf::from("docs/a.md")));
+        assert_eq!(command.preset.unwrap().name, "gemini");
+
+        let command = ConfigCommand::from_args(&args(&[])).unwrap();
+        assert_eq!(command.action, ConfigAction::Help);
+    }
+
+    #[test]
+    fn test_from_args_unknown_action() {
+        let error = ConfigCommand::from_args(&args(&["show"])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown config command `show`. Expected `print` or `explain`."
+        );
+    }
+
+    #[test]
+    fn test_leaves() {
+        let value = json!({
+            "markdown": { "enabled": true, "rules": { "headings": { "enabled": false } } },
+            "files": { "ignore": ["a/**"] }
+        });
+        assert_eq!(
+            leaves(&value),
+            vec![
+                ("files.ignore".to_string(), json!(["a/**"])),
+                ("markdown.enabled".to_string(), json!(true)),
+                ("markdown.rules.headings.enabled".to_string(), json!(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_value_drops_unset_values() {
+        let configuration = PartialMarkdownFormatterConfiguration {
+            files: Some(Default::default()),
+            root: true,
+            ..Default::default()
+        };
+        assert_eq!(to_value(&configuration), json!({ "files": {} }));
+    }
+
+    /// Runs `ripari config <args>` in a workspace whose configuration sets a value at the
+    /// top level and another in an override for `docs/`, with a preset declared in the file
+    fn run(args_: &[&str]) -> Vec<String> {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{
+  "preset": "gemini",
+  "markdown": { "rules": { "headings": { "enabled": false } } },
+  "overrides": [
+    {
+      "include": ["docs/**"],
+      "markdown": { "rules": { "math": { "delimiters": "dollars" } } }
+    }
+  ]
+}
+"#,
+        )
+        .unwrap();
+        fs::create_dir(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs/a.md"), "# A\n").unwrap();
+
+        let mut args_ = args(args_);
+        args_.push(dir.join("docs/a.md").display().to_string());
+        let console = MockConsole::new();
+        ConfigCommand::from_args(&args_)
+            .unwrap()
+            .execute(&console, &Workspace::with_root(dir))
+            .unwrap();
+        console.get_logs()
+    }
+
+    #[test]
+    fn test_print() {
+        let logs = run(&["print", "--rule", "emoji.body_text=remove"]);
+        assert_eq!(logs.len(), 1);
+        let printed: Value = serde_json::from_str(&logs[0]).unwrap();
+        let rules = &printed["markdown"]["rules"];
+        assert_eq!(rules["headings"]["enabled"], json!(false));
+        assert_eq!(rules["math"]["delimiters"], json!("dollars"));
+        assert_eq!(rules["emoji"]["enabled"], json!(true));
+        assert_eq!(rules["emoji"]["body_text"], json!("remove"));
+        assert!(printed.get("overrides").is_none());
+    }
+
+    #[test]
+    fn test_explain() {
+        let logs = run(&[
+            "explain",
+            "--preset=chatgpt",
+            "--rule",
+            "emoji.body_text=remove",
+        ]);
+        for expected in [
+            "markdown.rules.math.enabled = true  # default",
+            "markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true  # preset `chatgpt` from --preset",
+            "markdown.rules.emoji.enabled = true  # preset `gemini` at ./ripari.json:2:3",
+            "markdown.rules.headings.enabled = false  # ./ripari.json:3:42",
+            "markdown.rules.math.delimiters = \"dollars\"  # ./ripari.json:7:42 (overrides[0])",
+            "markdown.rules.emoji.body_text = \"remove\"  # flag `--rule emoji.body_text=remove`",
+        ] {
+            assert!(
+                logs.iter().any(|line| line == expected),
+                "missing `{}` in:\n{}",
+                expected,
+                logs.join("\n")
+            );
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use crate::commands::format::CommandRunner;
+use crate::console::Console;
+use crate::diagnostics::CliDiagnostic;
+use crate::origin::Origin;
+use crate::resolver::ConfigurationResolver;
+use crate::workspace::Workspace;
+
+#[derive(Debug, Clone, Default)]
+pub struct ExplainPathCommand {
+    pub file: Option<PathBuf>,
+    pub show_help: bool,
+}
+
+impl ExplainPathCommand {
+    /// Parses the arguments following `ripari explain-path`
+    pub fn from_args(args: &[String]) -> Result<Self, CliDiagnostic> {
+        let mut command = Self::default();
+        for arg in args {
+            match arg.as_str() {
+                "--help" | "-h" => command.show_help = true,
+                arg if arg.starts_with("--") => {
+                    return Err(CliDiagnostic::error(format!("Unknown flag `{}`", arg)));
+                }
+                arg if command.file.is_none() => command.file = Some(PathBuf::from(arg)),
+                arg => {
+                    return Err(CliDiagnostic::error(format!(
+                        "Unexpected argument `{}`, `ripari explain-path` takes a single file",
+                        arg
+                    )))
+                }
+            }
+        }
+        Ok(command)
+    }
+
+    fn print_help(console: &impl Console) {
+        console.log("Show whether a file is formatted and which patterns decided it.");
+        console.log("");
+        console.log("Usage: ripari explain-path <FILE>");
+        console.log("");
+        console.log("Available options:");
+        console.log("    -h, --help               Prints help information");
+    }
+}
+
+impl CommandRunner for ExplainPathCommand {
+    fn execute(&self, console: &impl Console, workspace: &Workspace) -> Result<(), CliDiagnostic> {
+        let Some(file) = self.file.as_ref().filter(|_| !self.show_help) else {
+            Self::print_help(console);
+            return Ok(());
+        };
+
+        let mut resolver = ConfigurationResolver::new(workspace.root());
+        let selection = resolver.select_file(file, console)?;
+        console.log(&format!(
+            "{}: {}",
+            file.display(),
+            selection.describe(resolver.root())
+        ));
+        if !selection.is_selected() {
+            return Ok(());
+        }
+        for layer in resolver.file_layers(file, console)? {
+            if let Origin::Override { .. } = &layer.origin {
+                console.log(&format!(
+                    "    override applies: {}",
+                    layer.origin.describe("include", resolver.root())
+                ));
+            }
+        }
+        Ok(())
+    }
+}
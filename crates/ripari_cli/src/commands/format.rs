@@ -1,18 +1,110 @@
 use std::ffi::OsString;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
 use crate::console::Console;
 use crate::diagnostics::CliDiagnostic;
+use crate::resolver::ConfigurationResolver;
+use crate::rule_overrides::RuleOverrides;
 use crate::workspace::Workspace;
-use simora_configuration:: PartialMarkdownFormatterConfiguration;
+use simora_configuration::{Preset, PRESET_NAMES};
 use simora_formatter::{Formatter, MarkdownFormatter};
 use simora_glob::Glob;
 
-/// Trait for commands that can load editor configuration
-pub trait LoadEditorConfig {
-    fn load_editor_config(&self) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic>;
+/// Characters making a path argument a glob pattern
+const GLOB_CHARACTERS: &[char] = &['*', '?', '[', '{'];
+
+/// The files named in `paths` and the Markdown files of the directories among them.
+/// Arguments with glob characters select the Markdown files they match and those
+/// starting with `!` exclude the files they match, whatever the shell.
+pub(crate) fn markdown_files(
+    paths: &[OsString],
+    console: &impl Console,
+) -> Result<Vec<PathBuf>, CliDiagnostic> {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for path in paths {
+        match path.to_str().and_then(|path| path.strip_prefix('!')) {
+            Some(pattern) => {
+                let pattern = pattern.trim_start_matches("./");
+                excluded
+                    .push(Glob::from_str(pattern).map_err(|e| CliDiagnostic::glob(pattern, &e))?);
+            }
+            None => included.push(Path::new(path)),
+        }
+    }
+    // Exclusions alone apply to the current directory
+    if included.is_empty() {
+        included.push(Path::new("."));
+    }
+
+    let mut files = Vec::new();
+    for path in included {
+        if path.is_file() {
+            files.push(path.to_path_buf());
+        } else if path.is_dir() {
+            let glob = Glob::from_str("**").expect("`**` is a valid glob");
+            walk_markdown_files(path, &glob, console, &mut files);
+        } else if let Some(pattern) = path.to_str().filter(|path| path.contains(GLOB_CHARACTERS)) {
+            let (directory, pattern) = split_glob(pattern);
+            let glob = Glob::from_str(pattern).map_err(|e| CliDiagnostic::glob(pattern, &e))?;
+            let count = files.len();
+            walk_markdown_files(&directory, &glob, console, &mut files);
+            if files.len() == count {
+                console.warn(&format!("No Markdown files match `{}`", path.display()));
+            }
+        }
+    }
+    files.retain(|file| {
+        // Compare paths as written, without `.` and the leading `/`
+        let file: PathBuf = file
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir | Component::RootDir))
+            .collect();
+        !excluded.iter().any(|glob| glob.is_match(&file))
+    });
+    Ok(files)
+}
+
+/// Splits a glob given on the command line into the directory to walk and the pattern
+/// matched against the paths inside it
+fn split_glob(pattern: &str) -> (PathBuf, &str) {
+    let mut start = 0;
+    for (index, _) in pattern.match_indices('/') {
+        if pattern[..index].contains(GLOB_CHARACTERS) {
+            break;
+        }
+        start = index + 1;
+    }
+    match &pattern[..start] {
+        "" => (PathBuf::from("."), pattern),
+        directory => (PathBuf::from(directory), &pattern[start..]),
+    }
+}
+
+/// Adds the Markdown files of `directory` whose path inside it matches `glob`
+fn walk_markdown_files(
+    directory: &Path,
+    glob: &Glob,
+    console: &impl Console,
+    files: &mut Vec<PathBuf>,
+) {
+    for entry in walkdir::WalkDir::new(directory) {
+        match entry {
+            Ok(entry) => {
+                let file = entry.path();
+                let relative = file.strip_prefix(directory).unwrap_or(file);
+                let is_markdown = file.extension().is_some_and(|extension| extension == "md");
+                if file.is_file() && is_markdown && glob.is_match(relative) {
+                    files.push(file.to_path_buf());
+                }
+            }
+            Err(e) => {
+                console.log(&format!("Error processing entry: {}", e));
+            }
+        }
+    }
 }
 
 /// Trait for executable commands
@@ -31,14 +123,10 @@ pub struct FormatCommand {
     pub changed: bool,
     pub since: Option<String>,
     pub verbose: bool,
-}
-
-impl LoadEditorConfig for FormatCommand {
-    fn load_editor_config(&self) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic> {
-        // Load configuration from workspace
-        let workspace = Workspace::new();
-        workspace.load_merged_configuration()
-    }
+    /// Built-in preset applied underneath the configuration files
+    pub preset: Option<&'static Preset>,
+    /// Rule settings from `--rule`, `--only`, `--skip` and `RIPARI_` variables
+    pub rule_overrides: RuleOverrides,
 }
 
 impl CommandRunner for FormatCommand {
@@ -56,8 +144,10 @@ impl CommandRunner for FormatCommand {
             workspace.root()
         ));
 
-        // Create formatter with global config
-        let mut formatter = MarkdownFormatter::new();
+        // Each file is formatted with the configuration resolved for its own path
+        let mut resolver = ConfigurationResolver::new(workspace.root())
+            .with_preset(self.preset)
+            .with_rule_overrides(self.rule_overrides.clone());
 
         // Process files based on VCS configuration if applicable
         let file_paths = if self.changed || self.staged || self.since.is_some() {
@@ -68,41 +158,32 @@ impl CommandRunner for FormatCommand {
             self.paths.clone()
         };
 
-        for path in file_paths {
-            let path = Path::new(&path);
-            if path.is_file() {
-                self.process_file(path, console, &formatter)?;
-            } else if path.is_dir() {
-                let glob_pattern = format!("{}/**/*.md", path.display());
-                let glob = Glob::from_str(&glob_pattern)
-                    .map_err(|e| CliDiagnostic::error(format!("Invalid glob pattern: {}", e)))?;
-
-                for entry in walkdir::WalkDir::new(path) {
-                    match entry {
-                        Ok(entry) => {
-                            let path = entry.path();
-                            if path.is_file() && glob.is_match(path.to_str().unwrap_or_default()) {
-                                self.process_file(path, console, &formatter)?;
-                            }
-                        }
-                        Err(e) => {
-                            console.log(&format!("Error processing entry: {}", e));
-                        }
-                    }
-                }
+        for path in markdown_files(&file_paths, console)? {
+            let selection = resolver.select_file(&path, console)?;
+            if !selection.is_selected() {
+                console.log(&format!(
+                    "Skipping {:?}: {}",
+                    path,
+                    selection.describe(resolver.root())
+                ));
+                continue;
             }
+            self.process_file(&path, console, &mut resolver)?;
         }
 
         // Handle stdin if provided
-        if let Some(_stdin_path) = &self.stdin_file_path {
+        if let Some(stdin_path) = &self.stdin_file_path {
             let mut buffer = String::new();
             std::io::stdin()
                 .read_line(&mut buffer)
                 .map_err(|e| CliDiagnostic::error(format!("Failed to read from stdin: {}", e)))?;
 
+            let formatter =
+                Self::formatter_for(Path::new(stdin_path), &buffer, console, &mut resolver)?;
             let formatted = formatter
                 .format_content(&buffer)
                 .map_err(|e| CliDiagnostic::error(format!("Failed to format content: {}", e)))?;
+            Self::warn_long_lines(&formatter, Path::new(stdin_path), &formatted, console);
             console.log(&formatted);
         }
 
@@ -129,6 +210,8 @@ impl FormatCommand {
             changed: false,
             since: None,
             verbose,
+            preset: None,
+            rule_overrides: RuleOverrides::default(),
         }
     }
 
@@ -144,11 +227,52 @@ impl FormatCommand {
         Ok(vec![])
     }
 
+    /// Create a formatter using the configuration that applies to `path`, including the
+    /// front matter of its `content`
+    pub(crate) fn formatter_for(
+        path: &Path,
+        content: &str,
+        console: &impl Console,
+        resolver: &mut ConfigurationResolver,
+    ) -> Result<MarkdownFormatter, CliDiagnostic> {
+        let mut config = resolver.resolve_document(path, content, console)?;
+        // Files without any Markdown configuration use the defaults
+        config.markdown.get_or_insert_with(Default::default);
+        let mut formatter = MarkdownFormatter::new();
+        formatter.apply_configuration(&config).map_err(|e| {
+            CliDiagnostic::error(format!(
+                "Failed to configure formatter for {:?}: {}",
+                path, e
+            ))
+        })?;
+        Ok(formatter)
+    }
+
+    /// Line length is only reported, ripari does not wrap prose
+    fn warn_long_lines(
+        formatter: &MarkdownFormatter,
+        path: &Path,
+        formatted: &str,
+        console: &impl Console,
+    ) {
+        let Some(max) = formatter.max_line_length() else {
+            return;
+        };
+        for line in formatter.long_lines(formatted) {
+            console.warn(&format!(
+                "{}:{} is longer than {} characters",
+                path.display(),
+                line,
+                max
+            ));
+        }
+    }
+
     fn process_file(
         &self,
         path: &Path,
         console: &impl Console,
-        formatter: &MarkdownFormatter,
+        resolver: &mut ConfigurationResolver,
     ) -> Result<(), CliDiagnostic> {
         if !path.exists() {
             return Err(CliDiagnostic::error(format!("File not found: {:?}", path)));
@@ -157,6 +281,8 @@ impl FormatCommand {
         let content = fs::read_to_string(path)
             .map_err(|e| CliDiagnostic::error(format!("Failed to read file {:?}: {}", path, e)))?;
 
+        let formatter = Self::formatter_for(path, &content, console, resolver)?;
+
         console.log(&format!("Processing file: {:?}", path));
         console.log(&format!("Original content length: {}", content.len()));
 
@@ -165,6 +291,7 @@ impl FormatCommand {
         })?;
 
         console.log(&format!("Formatted content length: {}", formatted.len()));
+        Self::warn_long_lines(&formatter, path, &formatted, console);
 
         if content == formatted {
             console.log("Content is identical after formatting");
@@ -218,11 +345,63 @@ impl FormatCommand {
         console
             .log("                              piped from stdin, and print the output to stdout");
         console.log("        --verbose             Show detailed debug information");
+        console.log(&format!(
+            "        --preset=NAME         Apply a built-in preset: {}",
+            PRESET_NAMES.join(", ")
+        ));
+        console.log("                              Configuration files override its settings");
+        console.log("        --rule=RULE[.OPTION]=VALUE");
+        console.log("                              Set a rule option, e.g. smart_quotes=off");
+        console.log("        --only=RULES          Enable only these comma-separated rules");
+        console.log("        --skip=RULES          Disable these comma-separated rules");
         console.log("");
         console.log("Available positional items:");
         console.log("    PATH                      Single file, single path or list of paths");
+        console.log("                              Globs such as 'notes/**/*.md' are expanded,");
+        console.log("                              and those starting with ! exclude files");
         console.log("");
         console.log("Available options:");
         console.log("    -h, --help               Prints help information");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::EnvConsole;
+    use crate::test_utils::temp_dir;
+
+    #[test]
+    fn test_split_glob() {
+        assert_eq!(
+            split_glob("./notes/**/*.md"),
+            (PathBuf::from("./notes/"), "**/*.md")
+        );
+        assert_eq!(split_glob("/a/b/*.md"), (PathBuf::from("/a/b/"), "*.md"));
+        assert_eq!(split_glob("*.md"), (PathBuf::from("."), "*.md"));
+        assert_eq!(
+            split_glob("docs/{a,b}/c.md"),
+            (PathBuf::from("docs/"), "{a,b}/c.md")
+        );
+    }
+
+    #[test]
+    fn test_expands_glob_arguments() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        for file in ["a.md", "b.txt", "drafts/c.md", "deep/er/d.md"] {
+            let path = dir.join("notes").join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "# Note\n").unwrap();
+        }
+        let notes = dir.join("notes");
+        let arguments = [
+            format!("{}/**/*", notes.display()),
+            format!("!{}/drafts/**", notes.display()),
+        ];
+        let arguments: Vec<OsString> = arguments.iter().map(OsString::from).collect();
+        let mut files = markdown_files(&arguments, &EnvConsole::new(false)).unwrap();
+        files.sort();
+        assert_eq!(files, [notes.join("a.md"), notes.join("deep/er/d.md")]);
+    }
+}
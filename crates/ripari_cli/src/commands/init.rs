@@ -0,0 +1,264 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::format::CommandRunner;
+use crate::console::Console;
+use crate::diagnostics::CliDiagnostic;
+use crate::workspace::Workspace;
+use serde_json::{json, Map, Value};
+use simora_configuration::{PartialMarkdownFormatterConfiguration, RULES};
+use simora_formatter::{Formatter, MarkdownFormatter};
+
+/// Name of the configuration file written by `ripari init` and `ripari migrate`
+const CONFIGURATION_FILE: &str = "ripari.json";
+
+#[derive(Debug, Clone, Default)]
+pub struct InitCommand {
+    /// Scan the workspace and only enable the rules that would change something
+    pub detect: bool,
+    pub show_help: bool,
+}
+
+/// The number of scanned files each rule would change
+#[derive(Debug, PartialEq, Eq)]
+pub struct Detection {
+    pub scanned: usize,
+    /// Affected files for every rule of the registry, in registry order
+    pub affected: Vec<(&'static str, usize)>,
+}
+
+impl InitCommand {
+    /// Parses the arguments following `ripari init`
+    pub fn from_args(args: &[String]) -> Result<Self, CliDiagnostic> {
+        let mut command = Self::default();
+        for arg in args {
+            match arg.as_str() {
+                "--detect" => command.detect = true,
+                "--help" | "-h" => command.show_help = true,
+                arg => {
+                    return Err(CliDiagnostic::error(format!(
+                        "Unknown argument `{}` for `ripari init`",
+                        arg
+                    )))
+                }
+            }
+        }
+        Ok(command)
+    }
+
+    fn print_help(console: &impl Console) {
+        console.log("Create a ripari.json in the current directory.");
+        console.log("");
+        console.log("Usage: ripari init [--detect]");
+        console.log("");
+        console.log("Available options:");
+        console.log("        --detect              Scan the Markdown files first and enable only");
+        console.log("                              the rules that would change them");
+        console.log("    -h, --help               Prints help information");
+    }
+}
+
+impl CommandRunner for InitCommand {
+    fn execute(&self, console: &impl Console, workspace: &Workspace) -> Result<(), CliDiagnostic> {
+        if self.show_help {
+            Self::print_help(console);
+            return Ok(());
+        }
+
+        let path = new_configuration_path(workspace)?;
+
+        let detection = if self.detect {
+            let detection = detect(workspace.root(), console)?;
+            console.log(&format!("Scanned {} Markdown files:", detection.scanned));
+            for (rule, files) in &detection.affected {
+                let status = if *files > 0 { "enabled" } else { "disabled" };
+                console.log(&format!(
+                    "    {:<25} {:>5} files affected, {}",
+                    rule, files, status
+                ));
+            }
+            Some(detection)
+        } else {
+            None
+        };
+
+        fs::write(&path, starter_configuration(detection.as_ref()))
+            .map_err(|e| CliDiagnostic::error(format!("Failed to write {:?}: {}", path, e)))?;
+        console.log(&format!("Created {}", path.display()));
+        Ok(())
+    }
+}
+
+/// The path of the `ripari.json` to create, which must not exist yet
+pub(crate) fn new_configuration_path(workspace: &Workspace) -> Result<PathBuf, CliDiagnostic> {
+    let path = workspace.root().join(CONFIGURATION_FILE);
+    if path.exists() {
+        return Err(CliDiagnostic::error(format!(
+            "{} already exists, remove it to start over",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// The content of a new `ripari.json`, enabling every rule unless a detection is given
+pub fn starter_configuration(detection: Option<&Detection>) -> String {
+    let rules = RULES.iter().map(|rule| {
+        let enabled = detection.is_none_or(|detection| {
+            detection
+                .affected
+                .iter()
+                .any(|(name, files)| *name == rule.name && *files > 0)
+        });
+        (rule.name.to_string(), json!({ "enabled": enabled }))
+    });
+    let configuration = json!({ "markdown": { "rules": Value::Object(rules.collect()) } });
+    format!("{:#}\n", configuration)
+}
+
+/// Counts the Markdown files under `root` that each rule would change on its own
+pub fn detect(root: &Path, console: &impl Console) -> Result<Detection, CliDiagnostic> {
+    // Comparing with every rule disabled leaves out changes made regardless of the rules
+    let baseline = formatter_with_only(None)?;
+    let formatters = RULES
+        .iter()
+        .map(|rule| Ok((rule.name, formatter_with_only(Some(rule.name))?)))
+        .collect::<Result<Vec<_>, CliDiagnostic>>()?;
+    let mut detection = Detection {
+        scanned: 0,
+        affected: RULES.iter().map(|rule| (rule.name, 0)).collect(),
+    };
+
+    let entries = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !is_skipped_directory(entry));
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_none_or(|extension| extension != "md") {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            console.log(&format!("Skipping unreadable file {:?}", path));
+            continue;
+        };
+        detection.scanned += 1;
+        let unchanged = format(&baseline, &content, path)?;
+        for ((_, formatter), (_, files)) in formatters.iter().zip(&mut detection.affected) {
+            if format(formatter, &content, path)? != unchanged {
+                *files += 1;
+            }
+        }
+    }
+    Ok(detection)
+}
+
+/// Hidden directories and installed packages are not part of the corpus
+fn is_skipped_directory(entry: &walkdir::DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.file_type().is_dir() && (name.starts_with('.') || name == "node_modules")
+}
+
+/// A formatter with every rule disabled but `rule`, which keeps its default options
+fn formatter_with_only(rule: Option<&str>) -> Result<MarkdownFormatter, CliDiagnostic> {
+    let rules: Map<String, Value> = RULES
+        .iter()
+        .map(|candidate| {
+            let enabled = rule == Some(candidate.name);
+            (candidate.name.to_string(), json!({ "enabled": enabled }))
+        })
+        .collect();
+    let markdown = serde_json::from_value(json!({ "enabled": true, "rules": rules }))
+        .map_err(|e| CliDiagnostic::error(format!("Invalid rule registry: {}", e)))?;
+    let mut formatter = MarkdownFormatter::new();
+    formatter
+        .apply_configuration(&PartialMarkdownFormatterConfiguration {
+            markdown: Some(markdown),
+            ..Default::default()
+        })
+        .map_err(|e| CliDiagnostic::error(format!("Failed to configure formatter: {}", e)))?;
+    Ok(formatter)
+}
+
+fn format(
+    formatter: &MarkdownFormatter,
+    content: &str,
+    path: &Path,
+) -> Result<String, CliDiagnostic> {
+    formatter
+        .format_content(content)
+        .map_err(|e| CliDiagnostic::error(format!("Failed to format file {:?}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::EnvConsole;
+    use crate::test_utils::temp_dir;
+
+    #[test]
+    fn test_detect_counts_affected_files() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(dir.join("a.md"), "# **Title**\n\nIt\u{2019}s here\n").unwrap();
+        fs::write(dir.join("b.md"), "Plain text \u{2014} with a dash\n").unwrap();
+        fs::write(dir.join("notes.txt"), "# **Ignored**\n").unwrap();
+        fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        fs::write(dir.join("node_modules/pkg/README.md"), "# **Ignored**\n").unwrap();
+
+        let detection = detect(dir, &EnvConsole::new(false)).unwrap();
+        assert_eq!(
+            detection,
+            Detection {
+                scanned: 2,
+                affected: vec![
+                    ("smart_quotes", 1),
+                    ("headings", 1),
+                    ("remove_horizontal_rules", 0),
+                    ("punctuation", 1),
+                    ("math", 0),
+                    ("emoji", 0),
+                    ("invisible_characters", 0),
+                    ("reasoning_blocks", 0),
+                ],
+            }
+        );
+
+        let configuration: Value =
+            serde_json::from_str(&starter_configuration(Some(&detection))).unwrap();
+        assert_eq!(
+            configuration["markdown"]["rules"],
+            json!({
+                "smart_quotes": { "enabled": true },
+                "headings": { "enabled": true },
+                "remove_horizontal_rules": { "enabled": false },
+                "punctuation": { "enabled": true },
+                "math": { "enabled": false },
+                "emoji": { "enabled": false },
+                "invisible_characters": { "enabled": false },
+                "reasoning_blocks": { "enabled": false },
+            })
+        );
+    }
+
+    #[test]
+    fn test_starter_configuration_is_valid() {
+        let content = starter_configuration(None);
+        let configuration = simora_configuration::parse_configuration(&content)
+            .unwrap()
+            .configuration;
+        let rules = configuration.markdown.unwrap().rules;
+        assert_eq!(rules.remove_horizontal_rules.enabled, Some(true));
+        assert_eq!(rules.smart_quotes.enabled, Some(true));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = vec!["--detect".to_string()];
+        assert!(InitCommand::from_args(&args).unwrap().detect);
+        let args = vec!["--force".to_string()];
+        assert_eq!(
+            InitCommand::from_args(&args).unwrap_err().to_string(),
+            "Unknown argument `--force` for `ripari init`"
+        );
+    }
+}
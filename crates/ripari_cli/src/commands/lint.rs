@@ -1,24 +1,33 @@
+use crate::commands::format::{markdown_files, FormatCommand};
 use crate::console::Console;
 use crate::diagnostics::CliDiagnostic;
+use crate::resolver::ConfigurationResolver;
+use crate::rule_overrides::RuleOverrides;
 use crate::workspace::Workspace;
+use simora_configuration::{find_rule, Preset};
+use simora_formatter::{parse_suppressions, MarkdownFormatter};
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
 
 #[derive(Debug, Default)]
 pub struct LintCommand {
-    // paths: Vec<OsString>,
+    pub paths: Vec<OsString>,
+    /// Built-in preset applied underneath the configuration files
+    pub preset: Option<&'static Preset>,
+    /// Rule settings from `--rule`, `--only`, `--skip` and `RIPARI_` variables
+    pub rule_overrides: RuleOverrides,
     // write: bool,
     // fix: bool,
     // stdin_file_path: Option<String>,
 }
 
 impl LintCommand {
-    pub fn new(
-
-    ) -> Self {
+    pub fn new(paths: Vec<OsString>, rule_overrides: RuleOverrides) -> Self {
         Self {
-            // write,
-            // fix,
-            // paths,
-            // stdin_file_path,
+            paths,
+            rule_overrides,
+            ..Default::default()
         }
     }
 
@@ -31,41 +40,86 @@ impl LintCommand {
             "Linting files in workspace: {:?}",
             workspace.root()
         ));
-        Ok(())
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::cell::RefCell;
-
-    struct MockConsole {
-        logs: RefCell<Vec<String>>,
-    }
-
-    impl Console for MockConsole {
-        fn log(&self, message: &str) {
-            self.logs.borrow_mut().push(message.to_string());
-        }
-
-        fn error(&self, message: &str) {
-            self.logs.borrow_mut().push(format!("ERROR: {}", message));
+        let mut resolver = ConfigurationResolver::new(workspace.root())
+            .with_preset(self.preset)
+            .with_rule_overrides(self.rule_overrides.clone());
+        let paths = match self.paths.is_empty() {
+            true => vec![workspace.root().into()],
+            false => self.paths.clone(),
+        };
+        for path in markdown_files(&paths, console)? {
+            if !resolver.select_file(&path, console)?.is_selected() {
+                continue;
+            }
+            let content = fs::read_to_string(&path).map_err(|e| {
+                CliDiagnostic::error(format!("Failed to read file {:?}: {}", path, e))
+            })?;
+            let formatter = FormatCommand::formatter_for(&path, &content, console, &mut resolver)?;
+            Self::check_suppressions(&path, &content, &formatter, console)?;
+            Self::check_invisible_characters(&path, &content, &formatter, console);
         }
+        Ok(())
     }
 
-    impl MockConsole {
-        fn new() -> Self {
-            Self {
-                logs: RefCell::new(Vec::new()),
+    /// Warns about suppression comments naming unknown rules or suppressing nothing
+    fn check_suppressions(
+        path: &Path,
+        content: &str,
+        formatter: &MarkdownFormatter,
+        console: &impl Console,
+    ) -> Result<(), CliDiagnostic> {
+        for directive in parse_suppressions(content) {
+            for rule in directive
+                .rules
+                .iter()
+                .filter(|rule| find_rule(rule).is_none())
+            {
+                console.warn(&format!(
+                    "{}:{} `{}` is not a rule",
+                    path.display(),
+                    directive.line,
+                    rule
+                ));
             }
         }
+        let unused = formatter.unused_suppressions(content).map_err(|e| {
+            CliDiagnostic::error(format!("Failed to format file {:?}: {}", path, e))
+        })?;
+        for directive in unused {
+            console.warn(&format!(
+                "{}:{} `{}` does not suppress anything",
+                path.display(),
+                directive.line,
+                directive
+            ));
+        }
+        Ok(())
+    }
 
-        fn get_logs(&self) -> Vec<String> {
-            self.logs.borrow().clone()
+    /// Points at each character the `invisible_characters` rule would change
+    fn check_invisible_characters(
+        path: &Path,
+        content: &str,
+        formatter: &MarkdownFormatter,
+        console: &impl Console,
+    ) {
+        for character in formatter.invisible_characters(content) {
+            console.warn(&format!(
+                "{}:{}:{} contains {}",
+                path.display(),
+                character.line,
+                character.column,
+                character
+            ));
         }
     }
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{temp_dir, MockConsole};
 
     #[test]
     fn test_lint_command_execution() {
@@ -81,4 +135,100 @@ mod tests {
         assert!(logs[0].contains("Linting files in workspace:"));
     }
 
+    #[test]
+    fn test_warns_about_unused_suppressions() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("guide.md"),
+            "<!-- ripari-disable-next-line punctuation -->\nAn em dash —\n\n\
+             <!-- ripari-disable smart_qoutes -->\nPlain text\n",
+        )
+        .unwrap();
+
+        let console = MockConsole::new();
+        let cmd = LintCommand::new(vec![dir.into()], RuleOverrides::default());
+        cmd.execute(&console, &Workspace::new()).unwrap();
+
+        let path = dir.join("guide.md");
+        let logs = console.get_logs();
+        assert_eq!(
+            logs[1..],
+            [
+                format!("ERROR: {}:4 `smart_qoutes` is not a rule", path.display()),
+                format!(
+                    "ERROR: {}:4 `ripari-disable smart_qoutes` does not suppress anything",
+                    path.display()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_points_at_invisible_characters() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "markdown": { "rules": { "invisible_characters": { "enabled": true } } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("notes.md"),
+            "Prix\u{202F}: 5\u{00A0}€\n\n```\nkept\u{200B}\n```\nin\u{00AD}visible\n",
+        )
+        .unwrap();
+
+        let console = MockConsole::new();
+        let cmd = LintCommand::new(vec![dir.into()], RuleOverrides::default());
+        cmd.execute(&console, &Workspace::new()).unwrap();
+
+        let path = dir.join("notes.md");
+        let logs = console.get_logs();
+        assert_eq!(
+            logs[1..],
+            [
+                format!(
+                    "ERROR: {}:1:5 contains U+202F narrow no-break space",
+                    path.display()
+                ),
+                format!(
+                    "ERROR: {}:1:9 contains U+00A0 no-break space",
+                    path.display()
+                ),
+                format!("ERROR: {}:6:3 contains U+00AD soft hyphen", path.display()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rule_flags_change_what_is_reported() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(dir.join("notes.md"), "5\u{00A0}€ in\u{00AD}visible\n").unwrap();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "markdown": { "rules": { "invisible_characters": { "enabled": true } } } }"#,
+        )
+        .unwrap();
+        let lint = |flags: &[&str]| {
+            let args: Vec<String> = flags.iter().map(|flag| flag.to_string()).collect();
+            let mut rule_overrides = RuleOverrides::default();
+            let mut i = 0;
+            while i < args.len() {
+                assert!(rule_overrides.parse_flag(&args, &mut i).unwrap());
+                i += 1;
+            }
+            let console = MockConsole::new();
+            let cmd = LintCommand::new(vec![dir.into()], rule_overrides);
+            cmd.execute(&console, &Workspace::new()).unwrap();
+            console.get_logs().len() - 1
+        };
+
+        assert_eq!(lint(&[]), 2);
+        assert_eq!(lint(&["--rule", "invisible_characters.spaces=off"]), 1);
+        let classes = "invisible_characters.spaces=off,invisible_characters.soft_hyphens=off";
+        assert_eq!(lint(&["--rule", classes]), 0);
+        assert_eq!(lint(&["--rule", "invisible_characters=off"]), 0);
+    }
 }
@@ -0,0 +1,82 @@
+use std::fs;
+
+use crate::commands::format::CommandRunner;
+use crate::commands::init::new_configuration_path;
+use crate::console::Console;
+use crate::diagnostics::CliDiagnostic;
+use crate::migration::Migration;
+use crate::workspace::Workspace;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default)]
+pub struct MigrateCommand {
+    pub show_help: bool,
+}
+
+impl MigrateCommand {
+    /// Parses the arguments following `ripari migrate`
+    pub fn from_args(args: &[String]) -> Result<Self, CliDiagnostic> {
+        let mut command = Self::default();
+        for arg in args {
+            match arg.as_str() {
+                "--help" | "-h" => command.show_help = true,
+                arg => {
+                    return Err(CliDiagnostic::error(format!(
+                        "Unknown argument `{}` for `ripari migrate`",
+                        arg
+                    )))
+                }
+            }
+        }
+        Ok(command)
+    }
+
+    fn print_help(console: &impl Console) {
+        console.log("Create a ripari.json from the markdownlint and Prettier configuration");
+        console.log("of the current directory.");
+        console.log("");
+        console.log("Usage: ripari migrate");
+        console.log("");
+        console.log("Reads .markdownlint.json, .markdownlint.yaml, .markdownlintrc, .prettierrc");
+        console.log("and their ignore files. Settings without a ripari equivalent are listed.");
+        console.log("");
+        console.log("Available options:");
+        console.log("    -h, --help               Prints help information");
+    }
+}
+
+impl CommandRunner for MigrateCommand {
+    fn execute(&self, console: &impl Console, workspace: &Workspace) -> Result<(), CliDiagnostic> {
+        if self.show_help {
+            Self::print_help(console);
+            return Ok(());
+        }
+
+        let path = new_configuration_path(workspace)?;
+
+        let migration = Migration::from_directory(workspace.root())?;
+        if migration.sources.is_empty() {
+            return Err(CliDiagnostic::error(format!(
+                "No markdownlint or Prettier configuration found in {}",
+                workspace.root().display()
+            )));
+        }
+
+        console.log(&format!("Read {}", migration.sources.join(", ")));
+        for line in &migration.migrated {
+            console.log(&format!("    {}", line));
+        }
+        if !migration.unsupported.is_empty() {
+            console.log("Not migrated:");
+            for line in &migration.unsupported {
+                console.log(&format!("    {}", line));
+            }
+        }
+
+        let content = format!("{:#}\n", Value::Object(migration.configuration));
+        fs::write(&path, content)
+            .map_err(|e| CliDiagnostic::error(format!("Failed to write {:?}: {}", path, e)))?;
+        console.log(&format!("Created {}", path.display()));
+        Ok(())
+    }
+}
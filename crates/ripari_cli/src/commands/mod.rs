@@ -1,14 +1,25 @@
 use crate::commands::format::CommandRunner;
 use crate::console::Console;
 use crate::diagnostics::CliDiagnostic;
+use crate::rule_overrides::RuleOverrides;
 use crate::workspace::Workspace;
+use simora_configuration::{did_you_mean, find_preset, Preset, PRESET_NAMES};
 
+pub mod config;
+pub mod explain_path;
 pub mod format;
+pub mod init;
 pub mod lint;
+pub mod migrate;
 
 #[derive(Debug)]
 pub enum SimoraCommand {
     Format(format::FormatCommand),
+    Lint(lint::LintCommand),
+    Config(config::ConfigCommand),
+    Init(init::InitCommand),
+    Migrate(migrate::MigrateCommand),
+    ExplainPath(explain_path::ExplainPathCommand),
 }
 
 impl SimoraCommand {
@@ -29,9 +40,20 @@ impl SimoraCommand {
                 let mut verbose = false;
                 let mut paths = Vec::new();
                 let mut stdin_file_path = None;
+                let mut preset = None;
+                let mut rule_overrides = RuleOverrides::from_env(env::vars())?;
 
                 let mut i = 2;
                 while i < args.len() {
+                    if rule_overrides.parse_flag(&args, &mut i)? {
+                        i += 1;
+                        continue;
+                    }
+                    if let Some(named) = parse_preset_flag(&args, &mut i)? {
+                        preset = Some(named);
+                        i += 1;
+                        continue;
+                    }
                     match args[i].as_str() {
                         "--write" => {
                             write = true;
@@ -48,6 +70,12 @@ impl SimoraCommand {
                                 i += 1;
                             }
                         }
+                        "--path" => {
+                            if i + 1 < args.len() {
+                                paths.push(OsString::from(&args[i + 1]));
+                                i += 1;
+                            }
+                        }
                         "--help" | "-h" => {
                             return Ok(SimoraCommand::Format(format::FormatCommand::with_help()));
                         }
@@ -61,14 +89,40 @@ impl SimoraCommand {
                     i += 1;
                 }
 
-                Ok(SimoraCommand::Format(format::FormatCommand::new(
-                    write,
-                    fix,
-                    paths,
-                    stdin_file_path,
-                    verbose,
-                )))
+                Ok(SimoraCommand::Format(format::FormatCommand {
+                    preset,
+                    rule_overrides,
+                    ..format::FormatCommand::new(write, fix, paths, stdin_file_path, verbose)
+                }))
+            }
+            "lint" => {
+                let mut command = lint::LintCommand {
+                    rule_overrides: RuleOverrides::from_env(env::vars())?,
+                    ..Default::default()
+                };
+                let mut i = 2;
+                while i < args.len() {
+                    if let Some(preset) = parse_preset_flag(&args, &mut i)? {
+                        command.preset = Some(preset);
+                    } else if !command.rule_overrides.parse_flag(&args, &mut i)? {
+                        command.paths.push(OsString::from(&args[i]));
+                    }
+                    i += 1;
+                }
+                Ok(SimoraCommand::Lint(command))
             }
+            "config" => Ok(SimoraCommand::Config(config::ConfigCommand::from_args(
+                &args[2..],
+            )?)),
+            "init" => Ok(SimoraCommand::Init(init::InitCommand::from_args(
+                &args[2..],
+            )?)),
+            "migrate" => Ok(SimoraCommand::Migrate(migrate::MigrateCommand::from_args(
+                &args[2..],
+            )?)),
+            "explain-path" => Ok(SimoraCommand::ExplainPath(
+                explain_path::ExplainPathCommand::from_args(&args[2..])?,
+            )),
             _ => Ok(SimoraCommand::Format(format::FormatCommand::with_help())),
         }
     }
@@ -80,50 +134,103 @@ impl SimoraCommand {
     ) -> Result<(), CliDiagnostic> {
         match self {
             SimoraCommand::Format(cmd) => cmd.execute(console, workspace),
+            SimoraCommand::Lint(cmd) => cmd.execute(console, workspace),
+            SimoraCommand::Config(cmd) => cmd.execute(console, workspace),
+            SimoraCommand::Init(cmd) => cmd.execute(console, workspace),
+            SimoraCommand::Migrate(cmd) => cmd.execute(console, workspace),
+            SimoraCommand::ExplainPath(cmd) => cmd.execute(console, workspace),
         }
     }
 }
 
+/// Parses `--preset NAME` or `--preset=NAME` at `args[*i]`, leaving `i` on its last
+/// argument
+fn parse_preset_flag(
+    args: &[String],
+    i: &mut usize,
+) -> Result<Option<&'static Preset>, CliDiagnostic> {
+    let name = match args[*i].strip_prefix("--preset") {
+        Some("") => {
+            *i += 1;
+            args.get(*i)
+                .ok_or_else(|| CliDiagnostic::error("Missing value for `--preset`"))?
+        }
+        Some(rest) => match rest.strip_prefix('=') {
+            Some(name) => name,
+            None => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+    parse_preset(name).map(Some)
+}
+
+/// Looks up the preset named on the command line
+fn parse_preset(name: &str) -> Result<&'static Preset, CliDiagnostic> {
+    find_preset(name).ok_or_else(|| {
+        let hint = match did_you_mean(name, PRESET_NAMES.iter().copied()) {
+            Some(suggestion) => format!("Did you mean `{}`?", suggestion),
+            None => format!("Available presets are {}.", PRESET_NAMES.join(", ")),
+        };
+        CliDiagnostic::error(format!("Unknown preset `{}`. {}", name, hint))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::cell::RefCell;
+    use crate::test_utils::MockConsole;
     // use std::ffi::OsString;
 
-    struct MockConsole {
-        logs: RefCell<Vec<String>>,
-    }
-
-    impl Console for MockConsole {
-        fn log(&self, message: &str) {
-            self.logs.borrow_mut().push(message.to_string());
-        }
-
-        fn error(&self, message: &str) {
-            self.logs.borrow_mut().push(format!("ERROR: {}", message));
-        }
+    #[test]
+    fn test_command_from_args() {
+        let cmd = SimoraCommand::from_args();
+        assert!(cmd.is_ok());
+        assert!(
+            matches!(cmd.unwrap(), SimoraCommand::Format(_)),
+            "Expected Format command"
+        );
     }
 
-    impl MockConsole {
-        fn new() -> Self {
-            Self {
-                logs: RefCell::new(Vec::new()),
-            }
-        }
-
-        fn get_logs(&self) -> Vec<String> {
-            self.logs.borrow().clone()
-        }
+    #[test]
+    fn test_parse_preset_flag() {
+        let args: Vec<String> = [
+            "ripari",
+            "lint",
+            "--preset",
+            "gemini",
+            "--preset=copilot",
+            "a.md",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let mut i = 2;
+        assert_eq!(
+            parse_preset_flag(&args, &mut i).unwrap().unwrap().name,
+            "gemini"
+        );
+        assert_eq!(i, 3);
+        i += 1;
+        assert_eq!(
+            parse_preset_flag(&args, &mut i).unwrap().unwrap().name,
+            "copilot"
+        );
+        i += 1;
+        assert!(parse_preset_flag(&args, &mut i).unwrap().is_none());
+        assert_eq!(i, 5);
     }
 
     #[test]
-    fn test_command_from_args() {
-        let cmd = SimoraCommand::from_args();
-        assert!(cmd.is_ok());
-        match cmd.unwrap() {
-            SimoraCommand::Format(_) => (),
-            _ => panic!("Expected Format command"),
-        }
+    fn test_parse_preset() {
+        assert_eq!(parse_preset("gemini").unwrap().name, "gemini");
+        assert_eq!(
+            parse_preset("deepsek").unwrap_err().to_string(),
+            "Unknown preset `deepsek`. Did you mean `deepseek`?"
+        );
+        assert_eq!(
+            parse_preset("claude").unwrap_err().to_string(),
+            "Unknown preset `claude`. Available presets are chatgpt, gemini, copilot, deepseek."
+        );
     }
 
     #[test]
@@ -140,7 +247,6 @@ mod tests {
         assert!(logs[0].contains("Formatting files in workspace:"));
     }
 
-
     #[test]
     fn test_command_debug_format() {
         let cmd = SimoraCommand::Format(format::FormatCommand::default());
@@ -1,6 +1,11 @@
 pub trait Console {
     fn log(&self, message: &str);
     fn error(&self, message: &str);
+
+    /// Reports a non-fatal problem, shown even when not verbose
+    fn warn(&self, message: &str) {
+        self.error(message);
+    }
 }
 
 #[derive(Debug)]
@@ -24,6 +29,10 @@ impl Console for EnvConsole {
     fn error(&self, message: &str) {
         eprintln!("Error: {}", message);
     }
+
+    fn warn(&self, message: &str) {
+        eprintln!("Warning: {}", message);
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,7 @@
+use simora_configuration::ConfigurationDiagnostic;
+use simora_glob::GlobError;
 use std::fmt;
+use std::path::Path;
 
 #[derive(Debug)]
 pub struct CliDiagnostic {
@@ -15,6 +18,47 @@ impl CliDiagnostic {
     pub fn error<T: Into<String>>(message: T) -> Self {
         Self::new(message)
     }
+
+    /// Reports every problem found in a configuration file
+    pub fn configuration(path: &Path, diagnostics: &[ConfigurationDiagnostic]) -> Self {
+        let lines: Vec<String> = diagnostics
+            .iter()
+            .map(|diagnostic| format_configuration_diagnostic(path, diagnostic))
+            .collect();
+        Self::new(format!(
+            "Invalid configuration file {}\n{}",
+            path.display(),
+            lines.join("\n")
+        ))
+    }
+
+    /// Reports an invalid glob given on the command line, pointing at the offending character
+    pub fn glob(pattern: &str, error: &GlobError) -> Self {
+        let mut message = format!("Invalid glob pattern `{}`: {}", pattern, error);
+        for line in error.highlight(pattern).iter().flat_map(|h| h.lines()) {
+            message.push_str(&format!("\n    {}", line));
+        }
+        if let Some(hint) = error.suggestion(pattern) {
+            message.push_str(&format!("\n    help: {}", hint));
+        }
+        Self::new(message)
+    }
+}
+
+/// Formats a configuration diagnostic as `file:line:column severity: message`, followed
+/// by its notes
+pub fn format_configuration_diagnostic(
+    path: &Path,
+    diagnostic: &ConfigurationDiagnostic,
+) -> String {
+    let mut formatted = match diagnostic.location {
+        Some(location) => format!("{}:{} {}", path.display(), location, diagnostic),
+        None => format!("{} {}", path.display(), diagnostic),
+    };
+    for note in &diagnostic.notes {
+        formatted.push_str(&format!("\n    {}", note));
+    }
+    formatted
 }
 
 impl fmt::Display for CliDiagnostic {
@@ -47,6 +91,37 @@ mod tests {
         assert_eq!(diagnostic.to_string(), "test message");
     }
 
+    #[test]
+    fn test_configuration_diagnostic() {
+        let diagnostics = simora_configuration::parse_configuration(
+            "{\n  \"markdown\": { \"rules\": { \"smart_qoutes\": {} } }\n}",
+        )
+        .unwrap_err();
+        let diagnostic = CliDiagnostic::configuration(Path::new("ripari.json"), &diagnostics);
+        assert_eq!(
+            diagnostic.to_string(),
+            "Invalid configuration file ripari.json\n\
+             ripari.json:2:28 error: Unknown key `smart_qoutes` in `markdown.rules`. Did you mean `smart_quotes`?"
+        );
+    }
+
+    #[test]
+    fn test_glob_diagnostic() {
+        let diagnostics = simora_configuration::parse_configuration(
+            "{\n  \"files\": { \"ignore\": [\"build/**\", \"docs/{a\"] }\n}",
+        )
+        .unwrap_err();
+        let diagnostic = CliDiagnostic::configuration(Path::new("ripari.json"), &diagnostics);
+        assert_eq!(
+            diagnostic.to_string(),
+            "Invalid configuration file ripari.json\n\
+             ripari.json:2:37 error: Invalid glob `docs/{a` in `files.ignore[1]`: Alternates `{` must be closed by `}`.\n    \
+             docs/{a\n    \
+             \x20    ^\n    \
+             help: Use `docs/\\{a` to match `{` literally."
+        );
+    }
+
     #[test]
     fn test_error_trait_implementation() {
         let error: Box<dyn std::error::Error> = Box::new(CliDiagnostic::error("test error"));
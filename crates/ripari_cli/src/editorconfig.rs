@@ -0,0 +1,232 @@
+use crate::origin::{ConfigurationLayer, Origin};
+use simora_configuration::{MarkdownFormatterConfig, PartialMarkdownFormatterConfiguration};
+use simora_glob::Glob;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// The `.editorconfig` properties ripari applies, all named as in `markdown`
+const PROPERTIES: &[&str] = &[
+    "end_of_line",
+    "insert_final_newline",
+    "trim_trailing_whitespace",
+    "indent_style",
+    "max_line_length",
+];
+
+/// A parsed `.editorconfig` file
+#[derive(Debug)]
+pub struct EditorConfig {
+    pub path: PathBuf,
+    root: bool,
+    sections: Vec<Section>,
+}
+
+/// A `[glob]` section and the supported properties it sets, lowercased
+#[derive(Debug)]
+struct Section {
+    glob: String,
+    line: usize,
+    properties: Vec<(String, String)>,
+}
+
+impl EditorConfig {
+    pub fn parse(path: PathBuf, content: &str) -> Self {
+        let mut config = Self {
+            path,
+            root: false,
+            sections: Vec::new(),
+        };
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+            if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                config.sections.push(Section {
+                    glob: glob.to_string(),
+                    line: index + 1,
+                    properties: Vec::new(),
+                });
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().to_ascii_lowercase();
+            match config.sections.last_mut() {
+                Some(section) if PROPERTIES.contains(&key.as_str()) => {
+                    section.properties.push((key, value))
+                }
+                Some(_) => {}
+                None => config.root |= key == "root" && value == "true",
+            }
+        }
+        config
+    }
+}
+
+impl Section {
+    /// Sections without `/` match file names at any depth, others paths relative to the
+    /// `.editorconfig`
+    fn matches(&self, relative: &Path) -> bool {
+        let glob = match self.glob.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if self.glob.contains('/') => self.glob.clone(),
+            None => format!("**/{}", self.glob),
+        };
+        Glob::from_str(&glob).is_ok_and(|glob| glob.is_match(relative))
+    }
+}
+
+/// Finds and caches the `.editorconfig` files that apply to each file
+#[derive(Debug, Default)]
+pub struct EditorConfigResolver {
+    loaded: HashMap<PathBuf, Option<Rc<EditorConfig>>>,
+}
+
+impl EditorConfigResolver {
+    /// Layers for the `.editorconfig` sections matching `path`, from lowest to highest
+    /// precedence. Each layer only holds the properties its section has the final say on.
+    pub fn layers(&mut self, path: &Path) -> Vec<ConfigurationLayer> {
+        let mut files = Vec::new();
+        for directory in path.ancestors().skip(1) {
+            if let Some(file) = self.load(&directory.join(".editorconfig")) {
+                let root = file.root;
+                files.push(file);
+                if root {
+                    break;
+                }
+            }
+        }
+
+        // Later sections and closer files win, `unset` removes a property
+        let mut properties: BTreeMap<&str, (&str, usize)> = BTreeMap::new();
+        let mut sections = Vec::new();
+        for file in files.iter().rev() {
+            let directory = file.path.parent().unwrap_or(Path::new("/"));
+            let relative = path.strip_prefix(directory).unwrap_or(path);
+            for section in file
+                .sections
+                .iter()
+                .filter(|section| section.matches(relative))
+            {
+                for (key, value) in &section.properties {
+                    if value == "unset" {
+                        properties.remove(key.as_str());
+                    } else {
+                        properties.insert(key, (value, sections.len()));
+                    }
+                }
+                sections.push((file, section));
+            }
+        }
+
+        let mut layers = Vec::new();
+        for (index, (file, section)) in sections.iter().enumerate() {
+            let mut markdown = MarkdownFormatterConfig::default();
+            let mut is_empty = true;
+            for (key, (value, _)) in properties.iter().filter(|(_, (_, at))| *at == index) {
+                is_empty &= !apply(&mut markdown, key, value);
+            }
+            if is_empty {
+                continue;
+            }
+            layers.push(ConfigurationLayer {
+                origin: Origin::EditorConfig {
+                    path: file.path.clone(),
+                    line: section.line,
+                    section: section.glob.clone(),
+                },
+                configuration: PartialMarkdownFormatterConfiguration {
+                    markdown: Some(markdown),
+                    ..Default::default()
+                },
+            });
+        }
+        layers
+    }
+
+    fn load(&mut self, path: &Path) -> Option<Rc<EditorConfig>> {
+        self.loaded
+            .entry(path.to_path_buf())
+            .or_insert_with(|| {
+                let content = fs::read_to_string(path).ok()?;
+                Some(Rc::new(EditorConfig::parse(path.to_path_buf(), &content)))
+            })
+            .clone()
+    }
+}
+
+/// Sets the `markdown` option matching an `.editorconfig` property. Returns whether the
+/// value was valid.
+fn apply(markdown: &mut MarkdownFormatterConfig, key: &str, value: &str) -> bool {
+    match (key, value) {
+        ("end_of_line", "lf" | "crlf" | "cr") => markdown.end_of_line = Some(value.to_string()),
+        ("indent_style", "space" | "tab") => markdown.indent_style = Some(value.to_string()),
+        ("insert_final_newline", "true" | "false") => {
+            markdown.insert_final_newline = Some(value == "true")
+        }
+        ("trim_trailing_whitespace", "true" | "false") => {
+            markdown.trim_trailing_whitespace = Some(value == "true")
+        }
+        ("max_line_length", _) => match value.parse() {
+            Ok(length) => markdown.max_line_length = Some(length),
+            Err(_) => return false,
+        },
+        _ => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+
+    #[test]
+    fn test_sections_resolve_per_file() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join(".editorconfig"),
+            "root = true\n\n[*]\nend_of_line = LF\ntrim_trailing_whitespace = true\n\n\
+             [*.{md,markdown}]\ntrim_trailing_whitespace = false\nmax_line_length = off\n\n\
+             [docs/**]\nend_of_line = crlf\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("docs/nested")).unwrap();
+        fs::write(
+            dir.join("docs/nested/.editorconfig"),
+            "[*.md]\nindent_style = space\nend_of_line = unset\n",
+        )
+        .unwrap();
+
+        let mut resolver = EditorConfigResolver::default();
+        let layers = resolver.layers(&dir.join("README.md"));
+        let origins: Vec<String> = layers
+            .iter()
+            .map(|layer| layer.origin.describe("", dir))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                "./.editorconfig:3 [*]",
+                "./.editorconfig:7 [*.{md,markdown}]"
+            ]
+        );
+        let markdown = crate::origin::merge_layers(&layers).markdown.unwrap();
+        assert_eq!(markdown.end_of_line.as_deref(), Some("lf"));
+        assert_eq!(markdown.trim_trailing_whitespace, Some(false));
+        assert_eq!(markdown.max_line_length, None);
+
+        let layers = resolver.layers(&dir.join("docs/nested/a.md"));
+        let markdown = crate::origin::merge_layers(&layers).markdown.unwrap();
+        assert_eq!(markdown.end_of_line, None);
+        assert_eq!(markdown.indent_style.as_deref(), Some("space"));
+        assert_eq!(markdown.trim_trailing_whitespace, Some(false));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::origin::{ConfigurationLayer, Origin};
+use simora_configuration::PartialFilesConfiguration;
+use simora_glob::{CandidatePath, Glob, GlobOptions, GlobSet};
+use std::path::{Path, PathBuf};
+
+/// A `files.include` or `files.ignore` list, compiled, and the layer that set it
+#[derive(Debug)]
+struct PatternList {
+    key: &'static str,
+    set: GlobSet,
+    /// The index in the configuration of each glob of `set`, invalid patterns being skipped
+    indices: Vec<usize>,
+    origin: Origin,
+    /// The directory the patterns are relative to
+    directory: PathBuf,
+}
+
+impl PatternList {
+    fn new(
+        key: &'static str,
+        patterns: &[String],
+        origin: &Origin,
+        root: &Path,
+        options: GlobOptions,
+    ) -> Self {
+        let (indices, globs) = patterns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, pattern)| Some((index, Glob::parse(pattern, options).ok()?)))
+            .unzip();
+        Self {
+            key,
+            set: GlobSet::new(globs),
+            indices,
+            origin: origin.clone(),
+            directory: origin.directory().unwrap_or(root).to_path_buf(),
+        }
+    }
+
+    /// The pattern deciding whether the absolute `path` matches. Files outside the
+    /// directory of the patterns never match.
+    fn deciding_pattern(&self, path: &Path) -> Option<Pattern> {
+        let relative = path.strip_prefix(&self.directory).ok()?;
+        let index = self.set.deciding_match(&CandidatePath::new(&relative))?;
+        let glob = &self.set.globs()[index];
+        Some(Pattern {
+            key: format!("{}[{}]", self.key, self.indices[index]),
+            glob: glob.to_string(),
+            is_negated: glob.is_negated(),
+            origin: self.origin.clone(),
+        })
+    }
+}
+
+/// The pattern that decided whether a file is selected
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// The configuration path of the pattern, e.g. `files.ignore[3]`
+    pub key: String,
+    pub glob: String,
+    pub is_negated: bool,
+    pub origin: Origin,
+}
+
+/// Whether a file is processed, and why
+#[derive(Debug, Clone)]
+pub enum Selection {
+    /// No `files` patterns apply
+    Selected,
+    /// Matched by `files.include`, or made an exception of by a negated `files.ignore` pattern
+    SelectedBy(Pattern),
+    IgnoredBy(Pattern),
+    /// `files.include` is set without matching the file, `None` meaning no pattern matched
+    NotIncluded {
+        origin: Origin,
+        excluded_by: Option<Pattern>,
+    },
+}
+
+impl Selection {
+    pub fn is_selected(&self) -> bool {
+        matches!(self, Selection::Selected | Selection::SelectedBy(_))
+    }
+
+    /// Describes the selection with origins relative to `root`
+    pub fn describe(&self, root: &Path) -> String {
+        let pattern = |pattern: &Pattern| {
+            format!(
+                "`{}` (`{}`) in {}",
+                pattern.key,
+                pattern.glob,
+                pattern.origin.describe(&pattern.key, root)
+            )
+        };
+        match self {
+            Selection::Selected => "selected, no `files` patterns apply".to_string(),
+            Selection::SelectedBy(by) if by.key.starts_with("files.ignore") => {
+                format!("selected as an exception by {}", pattern(by))
+            }
+            Selection::SelectedBy(by) => format!("included by {}", pattern(by)),
+            Selection::IgnoredBy(by) => format!("ignored by {}", pattern(by)),
+            Selection::NotIncluded {
+                excluded_by: Some(by),
+                ..
+            } => format!("excluded by {}", pattern(by)),
+            Selection::NotIncluded { origin, .. } => format!(
+                "not matched by `files.include` in {}",
+                origin.describe("files.include", root)
+            ),
+        }
+    }
+}
+
+/// The `files.include` and `files.ignore` lists that apply to a file
+#[derive(Debug, Default)]
+pub struct FileSelection {
+    include: Option<PatternList>,
+    ignore: Option<PatternList>,
+}
+
+impl FileSelection {
+    /// Compiles the lists set by the highest precedence layers, which replace lower ones.
+    /// Patterns of layers without a configuration file are relative to `root`.
+    pub fn from_layers(layers: &[ConfigurationLayer], root: &Path) -> Self {
+        let options = glob_options(layers);
+        let mut selection = Self::default();
+        for layer in layers {
+            let Some(PartialFilesConfiguration {
+                include, ignore, ..
+            }) = &layer.configuration.files
+            else {
+                continue;
+            };
+            let list =
+                |key, patterns| PatternList::new(key, patterns, &layer.origin, root, options);
+            if let Some(include) = include {
+                selection.include = Some(list("files.include", include));
+            }
+            if let Some(ignore) = ignore {
+                selection.ignore = Some(list("files.ignore", ignore));
+            }
+        }
+        selection
+    }
+
+    /// Whether the absolute `path` is selected. Ignoring takes precedence over including.
+    pub fn select(&self, path: &Path) -> Selection {
+        let ignored_by = self
+            .ignore
+            .as_ref()
+            .and_then(|ignore| ignore.deciding_pattern(path));
+        match ignored_by {
+            Some(pattern) if !pattern.is_negated => return Selection::IgnoredBy(pattern),
+            Some(pattern) if self.include.is_none() => return Selection::SelectedBy(pattern),
+            _ => {}
+        }
+        let Some(include) = &self.include else {
+            return Selection::Selected;
+        };
+        match include.deciding_pattern(path) {
+            Some(pattern) if !pattern.is_negated => Selection::SelectedBy(pattern),
+            excluded_by => Selection::NotIncluded {
+                origin: include.origin.clone(),
+                excluded_by,
+            },
+        }
+    }
+}
+
+/// How the patterns of `files` and `overrides` match, as set by the highest precedence layer
+pub fn glob_options(layers: &[ConfigurationLayer]) -> GlobOptions {
+    let case_sensitive = layers
+        .iter()
+        .rev()
+        .find_map(|layer| layer.configuration.files.as_ref()?.case_sensitive);
+    GlobOptions {
+        case_sensitive: case_sensitive.unwrap_or(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::origin::ConfigurationSource;
+    use simora_configuration::PartialMarkdownFormatterConfiguration;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    fn layer(content: &str) -> ConfigurationLayer {
+        layer_in("/project/ripari.json", content)
+    }
+
+    fn layer_in(path: &str, content: &str) -> ConfigurationLayer {
+        ConfigurationLayer {
+            origin: Origin::File(Rc::new(ConfigurationSource {
+                path: PathBuf::from(path),
+                content: content.to_string(),
+                directory: Path::new(path).parent().unwrap().to_path_buf(),
+            })),
+            configuration: PartialMarkdownFormatterConfiguration {
+                files: serde_json::from_str::<serde_json::Value>(content)
+                    .ok()
+                    .and_then(|value| serde_json::from_value(value["files"].clone()).ok()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_reports_deciding_pattern() {
+        let selection = FileSelection::from_layers(
+            &[layer(
+                r#"{
+  "files": {
+    "include": ["**/*.md", "!drafts/**"],
+    "ignore": ["build/**", "**/vendor/**", "!**/vendor/docs/**"]
+  }
+}"#,
+            )],
+            Path::new("/project"),
+        );
+        let root = Path::new("/project");
+        let select = |path: &str| selection.select(&root.join(path));
+        let describe = |path: &str| select(path).describe(root);
+
+        assert_eq!(
+            describe("lib/vendor/README.md"),
+            "ignored by `files.ignore[1]` (`**/vendor/**`) in ./ripari.json:4:28"
+        );
+        assert_eq!(
+            describe("drafts/post.md"),
+            "excluded by `files.include[1]` (`!drafts/**`) in ./ripari.json:3:28"
+        );
+        assert_eq!(
+            describe("lib/vendor/docs/guide.md"),
+            "included by `files.include[0]` (`**/*.md`) in ./ripari.json:3:17"
+        );
+        assert_eq!(
+            describe("notes.txt"),
+            "not matched by `files.include` in ./ripari.json:3:5"
+        );
+        assert!(select("README.md").is_selected());
+        assert!(!select("build/a.md").is_selected());
+    }
+
+    #[test]
+    fn test_later_layers_replace_lists() {
+        let selection = FileSelection::from_layers(
+            &[
+                layer(r#"{ "files": { "ignore": ["**"] } }"#),
+                layer(r#"{ "files": { "ignore": ["a/**", "!a/b.md"] } }"#),
+            ],
+            Path::new("/project"),
+        );
+        let root = Path::new("/project");
+        let select = |path: &str| selection.select(&root.join(path));
+        assert!(select("README.md").is_selected());
+        assert!(!select("a/c.md").is_selected());
+        assert_eq!(
+            select("a/b.md").describe(root),
+            "selected as an exception by `files.ignore[1]` (`!a/b.md`) in ./ripari.json:1:33"
+        );
+        assert!(FileSelection::default()
+            .select(Path::new("a.md"))
+            .is_selected());
+    }
+
+    #[test]
+    fn test_patterns_are_relative_to_their_file() {
+        let root = Path::new("/project");
+        let selection = FileSelection::from_layers(
+            &[
+                layer(r#"{ "files": { "case_sensitive": false } }"#),
+                layer_in(
+                    "/project/docs/ripari.json",
+                    r#"{ "files": { "ignore": ["/drafts/**", "**/*.TMP.md"] } }"#,
+                ),
+            ],
+            root,
+        );
+        let select = |path: &str| selection.select(&root.join(path));
+        assert!(!select("docs/drafts/a.md").is_selected());
+        assert!(!select("docs/Drafts/a.md").is_selected());
+        assert!(!select("docs/notes/a.tmp.md").is_selected());
+        assert!(select("docs/notes/drafts/a.md").is_selected());
+        assert!(select("drafts/a.md").is_selected());
+    }
+}
@@ -1,6 +1,16 @@
 pub mod commands;
 pub mod console;
 pub mod diagnostics;
+pub mod editorconfig;
+pub mod file_selection;
+pub mod loader;
+pub mod migration;
+pub mod origin;
+pub mod resolver;
+pub mod rule_overrides;
 pub mod workspace;
 
+#[cfg(test)]
+mod test_utils;
+
 pub use simora_glob as glob;
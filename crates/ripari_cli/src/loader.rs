@@ -0,0 +1,400 @@
+use crate::console::Console;
+use crate::diagnostics::{format_configuration_diagnostic, CliDiagnostic};
+use crate::origin::{merge_layers, ConfigurationLayer, ConfigurationSource, Origin};
+use simora_configuration::{
+    find_preset, parse_configuration_as, ConfigurationFormat,
+    PartialMarkdownFormatterConfiguration, CONFIGURATION_FILES,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// The configuration file of `directory`: the first of [CONFIGURATION_FILES] that exists.
+/// Lower precedence files next to it are reported and ignored.
+pub fn find_configuration_file(directory: &Path, console: &impl Console) -> Option<PathBuf> {
+    let mut found: Option<PathBuf> = None;
+    for (name, format) in CONFIGURATION_FILES {
+        let path = directory.join(name);
+        let has_configuration =
+            fs::read_to_string(&path).is_ok_and(|content| format.has_configuration(&content));
+        if !has_configuration {
+            continue;
+        }
+        match &found {
+            Some(used) => console.warn(&format!(
+                "{} is ignored, {} takes precedence",
+                path.display(),
+                used.display()
+            )),
+            None => found = Some(path),
+        }
+    }
+    found
+}
+
+/// Read and validate a configuration file, merging the files it `extends` underneath it
+pub fn load_configuration_file(
+    path: &Path,
+    console: &impl Console,
+) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic> {
+    Ok(merge_file_layers(&load_configuration_layers(
+        path, console,
+    )?))
+}
+
+/// Merges the layers of a single configuration file
+pub fn merge_file_layers(layers: &[ConfigurationLayer]) -> PartialMarkdownFormatterConfiguration {
+    let mut merged = merge_layers(layers);
+    // Whether a file is the root is never inherited
+    merged.root = layers.last().is_some_and(|layer| layer.configuration.root);
+    merged
+}
+
+/// Read and validate a configuration file, returning the files it `extends`, its preset
+/// and its own settings as separate layers, from lowest to highest precedence
+pub fn load_configuration_layers(
+    path: &Path,
+    console: &impl Console,
+) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+    let mut layers = Vec::new();
+    load_with_extends(path, None, console, &mut Vec::new(), &mut layers)?;
+    Ok(layers)
+}
+
+/// Loads `path` and the files it extends. Glob patterns of extended files are anchored at
+/// `anchor`, the directory of the outermost file declaring `extends`.
+fn load_with_extends(
+    path: &Path,
+    anchor: Option<&Path>,
+    console: &impl Console,
+    chain: &mut Vec<PathBuf>,
+    layers: &mut Vec<ConfigurationLayer>,
+) -> Result<(), CliDiagnostic> {
+    let canonical = path.canonicalize().map_err(|e| {
+        CliDiagnostic::error(format!(
+            "Failed to read config file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    if let Some(start) = chain.iter().position(|visited| *visited == canonical) {
+        let cycle: Vec<String> = chain[start..]
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(CliDiagnostic::error(format!(
+            "Circular `extends` detected: {}",
+            cycle.join(" -> ")
+        )));
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| CliDiagnostic::error(format!("Failed to read config file: {}", e)))?;
+
+    let format = ConfigurationFormat::from_path(path);
+    let parsed = parse_configuration_as(&content, format)
+        .map_err(|diagnostics| CliDiagnostic::configuration(path, &diagnostics))?;
+
+    for warning in &parsed.warnings {
+        console.warn(&format_configuration_diagnostic(path, warning));
+    }
+
+    let mut configuration = parsed.configuration;
+    let directory = canonical.parent().unwrap_or(Path::new("/")).to_path_buf();
+    let anchor = anchor.unwrap_or(&directory).to_path_buf();
+    let source = Rc::new(ConfigurationSource {
+        path: canonical.clone(),
+        content,
+        directory: anchor.clone(),
+    });
+
+    if let Some(extends) = configuration.extends.take() {
+        chain.push(canonical);
+        for (index, entry) in extends.iter().enumerate() {
+            let Some(extended_path) = resolve_extends_entry(entry, &directory) else {
+                let location = format
+                    .find_path(&source.content, &format!("extends[{}]", index))
+                    .map(|location| format!(":{}", location))
+                    .unwrap_or_default();
+                return Err(CliDiagnostic::error(format!(
+                    "{}{} error: Cannot find `{}` to extend. {}",
+                    path.display(),
+                    location,
+                    entry,
+                    missing_entry_hint(entry, &directory)
+                )));
+            };
+            load_with_extends(&extended_path, Some(&anchor), console, chain, layers)?;
+        }
+        chain.pop();
+    }
+
+    // Validation guarantees the preset exists
+    if let Some(preset) = configuration.preset.take().as_deref().and_then(find_preset) {
+        layers.push(ConfigurationLayer {
+            origin: Origin::Preset {
+                name: preset.name,
+                declared_in: Some(source.clone()),
+            },
+            configuration: PartialMarkdownFormatterConfiguration {
+                markdown: Some(preset.configuration()),
+                ..Default::default()
+            },
+        });
+    }
+
+    layers.push(ConfigurationLayer {
+        origin: Origin::File(source),
+        configuration,
+    });
+    Ok(())
+}
+
+fn is_relative_entry(entry: &str) -> bool {
+    entry.starts_with("./") || entry.starts_with("../") || Path::new(entry).is_absolute()
+}
+
+/// Paths resolve against the extending file's directory, anything else is looked up
+/// in the `node_modules` directories above it
+fn resolve_extends_entry(entry: &str, directory: &Path) -> Option<PathBuf> {
+    if is_relative_entry(entry) {
+        let path = directory.join(entry);
+        return path.is_file().then_some(path);
+    }
+
+    directory.ancestors().find_map(|ancestor| {
+        let candidate = ancestor.join("node_modules").join(entry);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let package_config = candidate.join("ripari.json");
+        package_config.is_file().then_some(package_config)
+    })
+}
+
+fn missing_entry_hint(entry: &str, directory: &Path) -> String {
+    if is_relative_entry(entry) {
+        format!("No file exists at {}.", directory.join(entry).display())
+    } else {
+        format!(
+            "It was not found in any `node_modules` directory above {}.",
+            directory.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::EnvConsole;
+    use crate::test_utils::temp_dir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("ripari.json");
+        write(
+            &path,
+            r#"{ "markdown": { "rules": { "smart_qoutes": { "enabled": false } } } }"#,
+        );
+
+        let error = load_configuration_file(&path, &EnvConsole::new(false)).unwrap_err();
+        assert!(error.to_string().contains("Did you mean `smart_quotes`?"));
+    }
+
+    #[test]
+    fn test_reports_syntax_errors_of_every_format() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("ripari.toml");
+        write(&path, "[markdown]\nenabled = \n");
+        let error = load_configuration_file(&path, &EnvConsole::new(false)).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("ripari.toml:2:11 error: invalid string"));
+
+        let path = dir.join("ripari.yaml");
+        write(&path, "markdown:\n  rules: [\n");
+        let error = load_configuration_file(&path, &EnvConsole::new(false)).unwrap_err();
+        assert!(error.to_string().contains("ripari.yaml:3:1 error:"));
+    }
+
+    #[test]
+    fn test_extends_relative_path_with_local_override() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        write(
+            &dir.join("presets/base.json"),
+            r#"{
+                "markdown": { "rules": { "punctuation": { "enabled": true, "standardize_dashes": true } } },
+                "files": { "ignore": ["**/vendor/**"] },
+                "root": true
+            }"#,
+        );
+        let path = dir.join("ripari.json");
+        write(
+            &path,
+            r#"{
+                "extends": ["./presets/base.json"],
+                "markdown": { "rules": { "punctuation": { "standardize_dashes": false } } }
+            }"#,
+        );
+
+        let config = load_configuration_file(&path, &EnvConsole::new(false)).unwrap();
+        let punctuation = config.markdown.unwrap().rules.punctuation;
+        assert_eq!(punctuation.enabled, Some(true));
+        assert_eq!(
+            punctuation.standardize_dashes,
+            Some(simora_configuration::StrategyOption::Enabled(false))
+        );
+        assert_eq!(config.files.unwrap().ignore.unwrap(), vec!["**/vendor/**"]);
+        assert!(config.extends.is_none());
+        assert!(!config.root);
+    }
+
+    #[test]
+    fn test_extends_later_entries_win() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        write(
+            &dir.join("a.json"),
+            r#"{ "markdown": { "rules": { "smart_quotes": { "enabled": true } } } }"#,
+        );
+        write(
+            &dir.join("b.json"),
+            r#"{ "markdown": { "rules": { "smart_quotes": { "enabled": false } } } }"#,
+        );
+        let path = dir.join("ripari.json");
+        write(&path, r#"{ "extends": ["./a.json", "./b.json"] }"#);
+
+        let config = load_configuration_file(&path, &EnvConsole::new(false)).unwrap();
+        assert_eq!(
+            config.markdown.unwrap().rules.smart_quotes.enabled,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_extends_package_from_node_modules() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        write(
+            &dir.join("node_modules/@acme/ripari-config/ripari.json"),
+            r#"{ "markdown": { "rules": { "headings": { "enabled": true } } } }"#,
+        );
+        let path = dir.join("docs/ripari.json");
+        write(&path, r#"{ "extends": ["@acme/ripari-config"] }"#);
+
+        let config = load_configuration_file(&path, &EnvConsole::new(false)).unwrap();
+        assert_eq!(config.markdown.unwrap().rules.headings.enabled, Some(true));
+    }
+
+    #[test]
+    fn test_extends_missing_preset() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("ripari.json");
+        write(
+            &path,
+            "{\n  \"extends\": [\"./base.json\", \"@acme/missing\"]\n}",
+        );
+
+        let error = load_configuration_file(&path, &EnvConsole::new(false))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("ripari.json:2:15 error: Cannot find `./base.json` to extend."));
+        assert!(error.contains("No file exists at"));
+
+        write(&dir.join("base.json"), "{}");
+        let error = load_configuration_file(&path, &EnvConsole::new(false))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Cannot find `@acme/missing` to extend."));
+        assert!(error.contains("not found in any `node_modules` directory"));
+    }
+
+    #[test]
+    fn test_preset_between_extends_and_local_settings() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        write(
+            &dir.join("base.json"),
+            r#"{ "markdown": { "rules": { "headings": { "enabled": false }, "smart_quotes": { "enabled": false } } } }"#,
+        );
+        let path = dir.join("ripari.json");
+        write(
+            &path,
+            r#"{
+                "extends": ["./base.json"],
+                "preset": "chatgpt",
+                "markdown": { "rules": {
+                    "smart_quotes": { "enabled": false },
+                    "punctuation": { "standardize_dashes": false }
+                } }
+            }"#,
+        );
+
+        let config = load_configuration_file(&path, &EnvConsole::new(false)).unwrap();
+        assert!(config.preset.is_none());
+        let rules = config.markdown.unwrap().rules;
+        assert_eq!(rules.headings.enabled, Some(true));
+        assert_eq!(rules.smart_quotes.enabled, Some(false));
+        assert_eq!(rules.punctuation.enabled, Some(true));
+        assert_eq!(
+            rules.punctuation.standardize_dashes,
+            Some(simora_configuration::StrategyOption::Enabled(false))
+        );
+    }
+
+    #[test]
+    fn test_layers_keep_their_origin() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        write(&dir.join("base.json"), "{}");
+        let path = dir.join("ripari.json");
+        write(
+            &path,
+            r#"{ "extends": ["./base.json"], "preset": "copilot" }"#,
+        );
+
+        let layers = load_configuration_layers(&path, &EnvConsole::new(false)).unwrap();
+        let origins: Vec<String> = layers
+            .iter()
+            .map(|layer| {
+                layer
+                    .origin
+                    .describe("preset", &dir.canonicalize().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                "./base.json",
+                "preset `copilot` at ./ripari.json:1:31",
+                "./ripari.json:1:31"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        write(&dir.join("a.json"), r#"{ "extends": ["./b.json"] }"#);
+        write(&dir.join("b.json"), r#"{ "extends": ["./a.json"] }"#);
+
+        let error = load_configuration_file(&dir.join("a.json"), &EnvConsole::new(false))
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("Circular `extends` detected:"));
+        assert!(error.ends_with("a.json"));
+        assert!(error.contains("b.json -> "));
+    }
+}
@@ -1,13 +1,8 @@
-mod commands;
-mod console;
-mod diagnostics;
-mod workspace;
-
-use commands::SimoraCommand;
-use console::{Console, EnvConsole};
-use diagnostics::CliDiagnostic;
+use ripari_cli::commands::SimoraCommand;
+use ripari_cli::console::{Console, EnvConsole};
+use ripari_cli::diagnostics::CliDiagnostic;
+use ripari_cli::workspace::Workspace;
 use std::process::ExitCode;
-use workspace::Workspace;
 
 fn main() -> ExitCode {
     let mut console = EnvConsole::new(true);
@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::diagnostics::CliDiagnostic;
+use serde_json::{json, Map, Value};
+use simora_configuration::find_markdownlint_equivalent;
+use simora_glob::Glob;
+
+/// markdownlint configuration files, JSON being read as YAML
+const MARKDOWNLINT_FILES: &[&str] = &[
+    ".markdownlint.json",
+    ".markdownlint.yaml",
+    ".markdownlint.yml",
+    ".markdownlintrc",
+];
+
+/// Prettier configuration files, JSON being read as YAML
+const PRETTIER_FILES: &[&str] = &[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+];
+
+/// Ignore files in `.gitignore` syntax, migrated to `files.ignore`
+const IGNORE_FILES: &[&str] = &[".markdownlintignore", ".prettierignore"];
+
+/// ripari settings equivalent to the markdownlint and Prettier files of a directory
+#[derive(Debug, Default)]
+pub struct Migration {
+    /// The files that were read, relative to the directory
+    pub sources: Vec<&'static str>,
+    /// The content of the `ripari.json` to write
+    pub configuration: Map<String, Value>,
+    /// One line for every migrated setting
+    pub migrated: Vec<String>,
+    /// One line for every setting without a ripari equivalent
+    pub unsupported: Vec<String>,
+}
+
+impl Migration {
+    /// Reads the markdownlint and Prettier files found in `dir`
+    pub fn from_directory(dir: &Path) -> Result<Self, CliDiagnostic> {
+        let mut migration = Self::default();
+        for file in MARKDOWNLINT_FILES.iter().chain(PRETTIER_FILES) {
+            if let Some(settings) = read_settings(&dir.join(file))? {
+                migration.sources.push(file);
+                if MARKDOWNLINT_FILES.contains(file) {
+                    migration.add_markdownlint(file, &settings);
+                } else {
+                    migration.add_prettier(file, &settings);
+                }
+            }
+        }
+        for file in IGNORE_FILES {
+            if let Ok(content) = fs::read_to_string(dir.join(file)) {
+                migration.sources.push(file);
+                migration.add_ignore_file(file, &content);
+            }
+        }
+        Ok(migration)
+    }
+
+    /// Maps each markdownlint rule through the markdownlint equivalents of the rule registry
+    fn add_markdownlint(&mut self, file: &str, settings: &Map<String, Value>) {
+        for (name, value) in settings {
+            let Some((rule, equivalent)) = find_markdownlint_equivalent(name) else {
+                self.unsupported
+                    .push(format!("`{}` in {} has no ripari equivalent", name, file));
+                continue;
+            };
+            // A rule configured with an object of options is enabled
+            let enabled = value.as_bool().unwrap_or(true);
+            if value.as_object().is_some_and(|options| !options.is_empty()) {
+                self.unsupported.push(format!(
+                    "The options of `{}` in {} have no ripari equivalent",
+                    name, file
+                ));
+            }
+            let rules = self.section("markdown").entry("rules").or_insert(json!({}));
+            rules[rule.name][equivalent.option] = Value::Bool(enabled);
+            self.migrated.push(format!(
+                "`{}` in {} → `markdown.rules.{}.{}` = {}",
+                name, file, rule.name, equivalent.option, enabled
+            ));
+        }
+    }
+
+    /// Prettier only wraps and indents prose, which ripari leaves alone
+    fn add_prettier(&mut self, file: &str, settings: &Map<String, Value>) {
+        for name in settings.keys() {
+            self.unsupported
+                .push(format!("`{}` in {} has no ripari equivalent", name, file));
+        }
+    }
+
+    fn add_ignore_file(&mut self, file: &str, content: &str) {
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            for glob in ignore_globs(line) {
+                if Glob::from_str(&glob).is_err() {
+                    self.unsupported.push(format!(
+                        "`{}` in {} is not a supported glob pattern",
+                        line, file
+                    ));
+                    break;
+                }
+                let ignore = self.section("files").entry("ignore").or_insert(json!([]));
+                let ignore = ignore.as_array_mut().expect("ignore is an array");
+                if ignore.contains(&Value::String(glob.clone())) {
+                    continue;
+                }
+                ignore.push(Value::String(glob.clone()));
+                self.migrated.push(format!(
+                    "`{}` in {} → `files.ignore` `{}`",
+                    line, file, glob
+                ));
+            }
+        }
+    }
+
+    fn section(&mut self, name: &str) -> &mut Map<String, Value> {
+        self.configuration
+            .entry(name)
+            .or_insert(json!({}))
+            .as_object_mut()
+            .expect("sections are objects")
+    }
+}
+
+/// Reads a JSON or YAML settings file, returning `None` if it does not exist
+fn read_settings(path: &Path) -> Result<Option<Map<String, Value>>, CliDiagnostic> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let settings: Value = serde_yaml::from_str(&content)
+        .map_err(|e| CliDiagnostic::error(format!("Failed to parse {:?}: {}", path, e)))?;
+    match settings {
+        Value::Object(settings) => Ok(Some(settings)),
+        Value::Null => Ok(Some(Map::new())),
+        _ => Err(CliDiagnostic::error(format!(
+            "Expected {:?} to contain an object of settings",
+            path
+        ))),
+    }
+}
+
+/// Translates a `.gitignore` pattern, which matches a file or a whole directory
+fn ignore_globs(pattern: &str) -> Vec<String> {
+    let (negation, pattern) = match pattern.strip_prefix('!') {
+        Some(pattern) => ("!", pattern),
+        None => ("", pattern),
+    };
+    let directory_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    // Patterns without an inner separator match at any depth
+    let glob = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if pattern.contains('/') || pattern.starts_with("**") => pattern.to_string(),
+        None => format!("**/{}", pattern),
+    };
+    let mut globs = vec![format!("{}{}/**", negation, glob)];
+    if !directory_only {
+        globs.insert(0, format!("{}{}", negation, glob));
+    }
+    globs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+
+    #[test]
+    fn test_ignore_globs() {
+        assert_eq!(ignore_globs("vendor/"), vec!["**/vendor/**"]);
+        assert_eq!(
+            ignore_globs("/CHANGELOG.md"),
+            vec!["CHANGELOG.md", "CHANGELOG.md/**"]
+        );
+        assert_eq!(ignore_globs("docs/api"), vec!["docs/api", "docs/api/**"]);
+        assert_eq!(ignore_globs("!keep/"), vec!["!**/keep/**"]);
+    }
+
+    #[test]
+    fn test_migrates_markdownlint_and_prettier() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join(".markdownlint.yaml"),
+            "default: true\nMD013: false\nhr-style: false\nMD036:\n  punctuation: \".,;:\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join(".prettierrc"), r#"{ "proseWrap": "always" }"#).unwrap();
+        fs::write(dir.join(".prettierignore"), "# generated\nvendor/\n").unwrap();
+
+        let migration = Migration::from_directory(dir).unwrap();
+        assert_eq!(
+            migration.sources,
+            vec![".markdownlint.yaml", ".prettierrc", ".prettierignore"]
+        );
+        assert_eq!(
+            Value::Object(migration.configuration),
+            json!({
+                "markdown": { "rules": { "headings": { "enabled": true } } },
+                "files": { "ignore": ["**/vendor/**"] }
+            })
+        );
+        assert_eq!(
+            migration.unsupported,
+            vec![
+                "`MD013` in .markdownlint.yaml has no ripari equivalent",
+                "The options of `MD036` in .markdownlint.yaml have no ripari equivalent",
+                "`default` in .markdownlint.yaml has no ripari equivalent",
+                "`hr-style` in .markdownlint.yaml has no ripari equivalent",
+                "`proseWrap` in .prettierrc has no ripari equivalent",
+            ]
+        );
+        assert_eq!(
+            migration.migrated[0],
+            "`MD036` in .markdownlint.yaml → `markdown.rules.headings.enabled` = true"
+        );
+    }
+
+    #[test]
+    fn test_reports_invalid_files() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(dir.join(".markdownlint.json"), "[1, 2]").unwrap();
+        let error = Migration::from_directory(dir).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("to contain an object of settings"));
+    }
+}
@@ -0,0 +1,192 @@
+use simora_configuration::{ConfigurationFormat, Merge, PartialMarkdownFormatterConfiguration};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// The content of a configuration file, kept to locate values when explaining them
+#[derive(Debug)]
+pub struct ConfigurationSource {
+    pub path: PathBuf,
+    pub content: String,
+    /// The directory the file's glob patterns are relative to: that of the file extending
+    /// it, or its own
+    pub directory: PathBuf,
+}
+
+impl ConfigurationSource {
+    /// `path` relative to `root` when possible, followed by the position of `key` if found
+    fn locate(&self, key: &str, root: &Path) -> String {
+        let path = relative_to(&self.path, root);
+        match ConfigurationFormat::from_path(&self.path).find_path(&self.content, key) {
+            Some(location) => format!("{}:{}", path.display(), location),
+            None => path.display().to_string(),
+        }
+    }
+}
+
+/// `path` relative to `root` when possible
+fn relative_to(path: &Path, root: &Path) -> PathBuf {
+    match path.strip_prefix(root) {
+        Ok(relative) => Path::new(".").join(relative),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Where a layer of configuration comes from
+#[derive(Debug, Clone)]
+pub enum Origin {
+    /// The formatter's built-in defaults
+    Default,
+    /// A built-in preset, selected by a configuration file or with `--preset`
+    Preset {
+        name: &'static str,
+        declared_in: Option<Rc<ConfigurationSource>>,
+    },
+    /// A configuration file, including files reached through `extends`
+    File(Rc<ConfigurationSource>),
+    /// An entry of the `overrides` array of a configuration file
+    Override {
+        file: Rc<ConfigurationSource>,
+        index: usize,
+    },
+    /// A section of an `.editorconfig` file, starting at `line`
+    EditorConfig {
+        path: PathBuf,
+        line: usize,
+        section: String,
+    },
+    /// The `ripari:` key of a document's front matter
+    FrontMatter(PathBuf),
+    /// A command line flag, as given
+    Flag(String),
+    /// A `RIPARI_` environment variable and its value
+    Environment(String),
+}
+
+impl Origin {
+    /// The directory the glob patterns of this origin's configuration file are relative to
+    pub fn directory(&self) -> Option<&Path> {
+        match self {
+            Origin::File(file) | Origin::Override { file, .. } => Some(&file.directory),
+            _ => None,
+        }
+    }
+
+    /// Describes where `key`, a JSON path such as `markdown.rules.headings.enabled`, was set
+    pub fn describe(&self, key: &str, root: &Path) -> String {
+        match self {
+            Origin::Default => "default".to_string(),
+            Origin::Preset {
+                name,
+                declared_in: None,
+            } => format!("preset `{}` from --preset", name),
+            Origin::Preset {
+                name,
+                declared_in: Some(file),
+            } => format!("preset `{}` at {}", name, file.locate("preset", root)),
+            Origin::File(file) => file.locate(key, root),
+            Origin::Override { file, index } => format!(
+                "{} (overrides[{}])",
+                file.locate(&format!("overrides[{}].{}", index, key), root),
+                index
+            ),
+            Origin::EditorConfig {
+                path,
+                line,
+                section,
+            } => format!(
+                "{}:{} [{}]",
+                relative_to(path, root).display(),
+                line,
+                section
+            ),
+            Origin::FrontMatter(path) => {
+                format!("front matter of {}", relative_to(path, root).display())
+            }
+            Origin::Flag(flag) => format!("flag `{}`", flag),
+            Origin::Environment(variable) => format!("environment variable `{}`", variable),
+        }
+    }
+}
+
+/// Configuration contributed by a single origin
+#[derive(Debug, Clone)]
+pub struct ConfigurationLayer {
+    pub origin: Origin,
+    pub configuration: PartialMarkdownFormatterConfiguration,
+}
+
+/// Merges `layers` in order, later layers taking precedence
+pub fn merge_layers(layers: &[ConfigurationLayer]) -> PartialMarkdownFormatterConfiguration {
+    let mut merged = PartialMarkdownFormatterConfiguration::default();
+    for layer in layers {
+        merged.merge_with(layer.configuration.clone());
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> Rc<ConfigurationSource> {
+        Rc::new(ConfigurationSource {
+            path: PathBuf::from("/project/docs/ripari.json"),
+            content: r#"{
+  "preset": "chatgpt",
+  "markdown": { "rules": { "headings": { "enabled": false } } },
+  "overrides": [{ "markdown": { "enabled": false } }]
+}"#
+            .to_string(),
+            directory: PathBuf::from("/project/docs"),
+        })
+    }
+
+    #[test]
+    fn test_describe_file_value() {
+        let origin = Origin::File(source());
+        let root = Path::new("/project");
+        assert_eq!(
+            origin.describe("markdown.rules.headings.enabled", root),
+            "./docs/ripari.json:3:42"
+        );
+        assert_eq!(
+            origin.describe("markdown.rules.headings.enabled", Path::new("/elsewhere")),
+            "/project/docs/ripari.json:3:42"
+        );
+    }
+
+    #[test]
+    fn test_describe_override_and_presets() {
+        let root = Path::new("/project");
+        let origin = Origin::Override {
+            file: source(),
+            index: 0,
+        };
+        assert_eq!(
+            origin.describe("markdown.enabled", root),
+            "./docs/ripari.json:4:33 (overrides[0])"
+        );
+
+        let origin = Origin::Preset {
+            name: "chatgpt",
+            declared_in: Some(source()),
+        };
+        assert_eq!(
+            origin.describe("markdown.enabled", root),
+            "preset `chatgpt` at ./docs/ripari.json:2:3"
+        );
+
+        let origin = Origin::Preset {
+            name: "gemini",
+            declared_in: None,
+        };
+        assert_eq!(
+            origin.describe("markdown.enabled", root),
+            "preset `gemini` from --preset"
+        );
+        assert_eq!(
+            Origin::Default.describe("markdown.enabled", root),
+            "default"
+        );
+    }
+}
@@ -0,0 +1,645 @@
+use crate::console::Console;
+use crate::diagnostics::CliDiagnostic;
+use crate::editorconfig::EditorConfigResolver;
+use crate::file_selection::{glob_options, FileSelection, Selection};
+use crate::loader::{find_configuration_file, load_configuration_layers, merge_file_layers};
+use crate::origin::{merge_layers, ConfigurationLayer, Origin};
+use crate::rule_overrides::RuleOverrides;
+use simora_configuration::{
+    parse_front_matter, MarkdownFormatterConfig, PartialMarkdownFormatterConfiguration, Preset,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Resolves the configuration that applies to each file.
+/// Configuration files are loaded once, however many files they apply to.
+#[derive(Debug)]
+pub struct ConfigurationResolver {
+    root: PathBuf,
+    /// Preset selected on the command line, applied underneath every configuration file
+    preset: Option<&'static Preset>,
+    /// Rule flags and environment variables, applied on top of everything else
+    rule_overrides: RuleOverrides,
+    loaded: HashMap<PathBuf, Vec<ConfigurationLayer>>,
+    /// The configuration file found in each directory visited so far
+    discovered: HashMap<PathBuf, Option<PathBuf>>,
+    /// `.editorconfig` files, applied underneath every configuration file
+    editorconfig: EditorConfigResolver,
+}
+
+impl ConfigurationResolver {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            preset: None,
+            rule_overrides: RuleOverrides::default(),
+            loaded: HashMap::new(),
+            discovered: HashMap::new(),
+            editorconfig: EditorConfigResolver::default(),
+        }
+    }
+
+    pub fn with_preset(mut self, preset: Option<&'static Preset>) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    pub fn with_rule_overrides(mut self, rule_overrides: RuleOverrides) -> Self {
+        self.rule_overrides = rule_overrides;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Find all configuration files from `directory` up to the nearest root configuration.
+    /// Returns them in order from most specific to least specific.
+    pub fn find_configurations(
+        &mut self,
+        directory: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<PartialMarkdownFormatterConfiguration>, CliDiagnostic> {
+        Ok(self
+            .find_configuration_layers(directory, console)?
+            .iter()
+            .map(|layers| merge_file_layers(layers))
+            .collect())
+    }
+
+    fn find_configuration_layers(
+        &mut self,
+        directory: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<Vec<ConfigurationLayer>>, CliDiagnostic> {
+        let mut configs = Vec::new();
+        let mut current_dir = directory
+            .canonicalize()
+            .map_err(|e| CliDiagnostic::error(format!("Failed to canonicalize path: {}", e)))?;
+
+        loop {
+            if let Some(config_path) = self.discover(&current_dir, console) {
+                let layers = self.load(&config_path, console)?;
+                // The file's own settings come last, `root` is never inherited
+                let is_root = layers.last().is_some_and(|layer| layer.configuration.root);
+                configs.push(layers);
+
+                if is_root {
+                    break;
+                }
+            }
+
+            if !current_dir.pop() {
+                break;
+            }
+        }
+
+        Ok(configs)
+    }
+
+    /// The layers of configuration that apply to files in `directory`, from lowest to
+    /// highest precedence. Overrides are left unapplied.
+    pub fn directory_layers(
+        &mut self,
+        directory: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        let mut layers = self.configured_layers(directory, console)?;
+        layers.extend_from_slice(self.rule_overrides.layers());
+        Ok(layers)
+    }
+
+    /// The command line preset followed by the configuration files of `directory`
+    fn configured_layers(
+        &mut self,
+        directory: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        let mut layers: Vec<ConfigurationLayer> = self
+            .preset
+            .map(|preset| ConfigurationLayer {
+                origin: Origin::Preset {
+                    name: preset.name,
+                    declared_in: None,
+                },
+                configuration: PartialMarkdownFormatterConfiguration {
+                    markdown: Some(preset.configuration()),
+                    ..Default::default()
+                },
+            })
+            .into_iter()
+            .collect();
+        for file_layers in self
+            .find_configuration_layers(directory, console)?
+            .into_iter()
+            .rev()
+        {
+            layers.extend(file_layers);
+        }
+        Ok(layers)
+    }
+
+    /// The layers of configuration that apply to `path`: those of its directory, each
+    /// configuration file followed by its matching overrides in declaration order
+    fn configured_file_layers(
+        &mut self,
+        path: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        let absolute = self.absolute(path);
+        let directory = absolute.parent().unwrap_or(&self.root).to_path_buf();
+        let mut directory_layers = self.editorconfig.layers(&absolute);
+        directory_layers.extend(self.configured_layers(&directory, console)?);
+        let options = glob_options(&directory_layers);
+
+        let mut layers = Vec::new();
+        for mut layer in directory_layers {
+            let entries = layer.configuration.overrides.take().unwrap_or_default();
+            let origin = layer.origin.clone();
+            layers.push(layer);
+            let Origin::File(file) = origin else {
+                continue;
+            };
+            // Patterns are relative to the file declaring them, or to the file extending it
+            let Ok(relative) = absolute.strip_prefix(&file.directory) else {
+                continue;
+            };
+            for (index, entry) in entries.into_iter().enumerate() {
+                if entry.matches(relative, options) {
+                    layers.push(ConfigurationLayer {
+                        origin: Origin::Override {
+                            file: file.clone(),
+                            index,
+                        },
+                        configuration: PartialMarkdownFormatterConfiguration {
+                            markdown: entry.markdown,
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+        }
+        Ok(layers)
+    }
+
+    /// The layers that apply to `path`, followed by the rule flags
+    pub fn file_layers(
+        &mut self,
+        path: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        let mut layers = self.configured_file_layers(path, console)?;
+        layers.extend_from_slice(self.rule_overrides.layers());
+        Ok(layers)
+    }
+
+    /// The layers that apply to `path` with `content`, whose front matter rule settings
+    /// take precedence over configuration files but not over the command line
+    pub fn document_layers(
+        &mut self,
+        path: &Path,
+        content: &str,
+        console: &impl Console,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        let mut layers = self.configured_file_layers(path, console)?;
+        let rules = parse_front_matter(content)
+            .map_err(|diagnostics| CliDiagnostic::configuration(path, &diagnostics))?;
+        if let Some(rules) = rules {
+            layers.push(ConfigurationLayer {
+                origin: Origin::FrontMatter(self.absolute(path)),
+                configuration: PartialMarkdownFormatterConfiguration {
+                    markdown: Some(MarkdownFormatterConfig {
+                        rules,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            });
+        }
+        layers.extend_from_slice(self.rule_overrides.layers());
+        Ok(layers)
+    }
+
+    /// Whether `path` is selected by the `files` patterns that apply to it
+    pub fn select_file(
+        &mut self,
+        path: &Path,
+        console: &impl Console,
+    ) -> Result<Selection, CliDiagnostic> {
+        let layers = self.file_layers(path, console)?;
+        let selection = FileSelection::from_layers(&layers, &self.root);
+        Ok(selection.select(&self.absolute(path)))
+    }
+
+    /// Resolve the configuration for `path` with `content`, including its front matter
+    pub fn resolve_document(
+        &mut self,
+        path: &Path,
+        content: &str,
+        console: &impl Console,
+    ) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic> {
+        Ok(merge_layers(&self.document_layers(path, content, console)?))
+    }
+
+    /// Merge the configurations that apply to files in `directory`
+    pub fn resolve_directory(
+        &mut self,
+        directory: &Path,
+        console: &impl Console,
+    ) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic> {
+        Ok(merge_layers(&self.directory_layers(directory, console)?))
+    }
+
+    /// Resolve the configuration of its directory for `path`, then apply matching overrides
+    pub fn resolve_file(
+        &mut self,
+        path: &Path,
+        console: &impl Console,
+    ) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic> {
+        Ok(merge_layers(&self.file_layers(path, console)?))
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        let joined = self.root.join(path);
+        // Files named through `--stdin-file-path` may not exist
+        joined.canonicalize().unwrap_or(joined)
+    }
+
+    fn discover(&mut self, directory: &Path, console: &impl Console) -> Option<PathBuf> {
+        self.discovered
+            .entry(directory.to_path_buf())
+            .or_insert_with(|| find_configuration_file(directory, console))
+            .clone()
+    }
+
+    fn load(
+        &mut self,
+        path: &Path,
+        console: &impl Console,
+    ) -> Result<Vec<ConfigurationLayer>, CliDiagnostic> {
+        if let Some(layers) = self.loaded.get(path) {
+            return Ok(layers.clone());
+        }
+        let layers = load_configuration_layers(path, console)?;
+        self.loaded.insert(path.to_path_buf(), layers.clone());
+        Ok(layers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::EnvConsole;
+    use crate::test_utils::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn test_nested_configuration_wins() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "root": true, "markdown": { "rules": { "smart_quotes": { "enabled": true } } } }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(
+            dir.join("docs/ripari.json"),
+            r#"{ "markdown": { "rules": { "smart_quotes": { "enabled": false } } } }"#,
+        )
+        .unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let console = EnvConsole::new(false);
+        let root = resolver.resolve_file(Path::new("a.md"), &console).unwrap();
+        let docs = resolver
+            .resolve_file(Path::new("docs/a.md"), &console)
+            .unwrap();
+        assert_eq!(
+            root.markdown.unwrap().rules.smart_quotes.enabled,
+            Some(true)
+        );
+        assert_eq!(
+            docs.markdown.unwrap().rules.smart_quotes.enabled,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_configuration_file_precedence() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.toml"),
+            "root = true\n\n[markdown.rules.headings]\nenabled = false\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("package.json"),
+            r#"{ "ripari": { "markdown": { "rules": { "headings": { "enabled": true } } } } }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(
+            dir.join("docs/package.json"),
+            r#"{ "name": "docs", "ripari": { "markdown": { "enabled": false } } }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("blog")).unwrap();
+        fs::write(dir.join("blog/package.json"), r#"{ "name": "blog" }"#).unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let console = EnvConsole::new(false);
+        let root = resolver.resolve_file(Path::new("a.md"), &console).unwrap();
+        assert_eq!(root.markdown.unwrap().rules.headings.enabled, Some(false));
+
+        let docs = resolver
+            .resolve_file(Path::new("docs/a.md"), &console)
+            .unwrap();
+        let docs = docs.markdown.unwrap();
+        assert_eq!(docs.enabled, Some(false));
+        assert_eq!(docs.rules.headings.enabled, Some(false));
+
+        // A package.json without a `ripari` key is not a configuration file
+        let blog = resolver
+            .find_configurations(&dir.join("blog"), &console)
+            .unwrap();
+        assert_eq!(blog.len(), 1);
+    }
+
+    #[test]
+    fn test_configuration_wins_over_editorconfig() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join(".editorconfig"),
+            "root = true\n\n[*.md]\nend_of_line = crlf\ninsert_final_newline = true\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "root": true, "markdown": { "end_of_line": "lf" } }"#,
+        )
+        .unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let console = EnvConsole::new(false);
+        let markdown = resolver
+            .resolve_file(Path::new("a.md"), &console)
+            .unwrap()
+            .markdown
+            .unwrap();
+        assert_eq!(markdown.end_of_line.as_deref(), Some("lf"));
+        assert_eq!(markdown.insert_final_newline, Some(true));
+    }
+
+    #[test]
+    fn test_front_matter_wins_over_configuration_files() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "root": true, "markdown": { "rules": { "punctuation": { "standardize_dashes": true, "standardize_ellipsis": false } } } }"#,
+        )
+        .unwrap();
+        let content =
+            "---\ntitle: Post\nripari:\n  punctuation:\n    standardize_dashes: false\n---\n";
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let console = EnvConsole::new(false);
+        let layers = resolver
+            .document_layers(Path::new("post.md"), content, &console)
+            .unwrap();
+        assert_eq!(
+            layers.last().unwrap().origin.describe("", dir),
+            "front matter of ./post.md"
+        );
+        let punctuation = merge_layers(&layers).markdown.unwrap().rules.punctuation;
+        assert_eq!(
+            punctuation.standardize_dashes,
+            Some(simora_configuration::StrategyOption::Enabled(false))
+        );
+        assert_eq!(punctuation.standardize_ellipsis, Some(false));
+
+        let error = resolver
+            .document_layers(Path::new("post.md"), "---\nripari: [1]\n---\n", &console)
+            .unwrap_err();
+        assert!(error.to_string().contains("post.md:2:1"));
+    }
+
+    #[test]
+    fn test_configuration_files_override_cli_preset() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "root": true, "markdown": { "rules": { "headings": { "enabled": false } } } }"#,
+        )
+        .unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir)
+            .with_preset(simora_configuration::find_preset("chatgpt"));
+        let config = resolver
+            .resolve_file(Path::new("a.md"), &EnvConsole::new(false))
+            .unwrap();
+        let rules = config.markdown.unwrap().rules;
+        assert_eq!(rules.headings.enabled, Some(false));
+        assert_eq!(rules.smart_quotes.enabled, Some(true));
+    }
+
+    #[test]
+    fn test_overrides_apply_in_order() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{
+                "root": true,
+                "overrides": [
+                    { "include": ["i18n/fr/**"], "markdown": { "rules": { "smart_quotes": { "enabled": false } } } },
+                    { "include": ["i18n/**"], "markdown": { "rules": { "smart_quotes": { "enabled": true } } } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("i18n/fr")).unwrap();
+        fs::write(
+            dir.join("i18n/fr/ripari.json"),
+            r#"{ "overrides": [{ "markdown": { "rules": { "headings": { "enabled": false } } } }] }"#,
+        )
+        .unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let layers = resolver
+            .file_layers(Path::new("i18n/fr/guide.md"), &EnvConsole::new(false))
+            .unwrap();
+        let origins: Vec<String> = layers
+            .iter()
+            .map(|layer| layer.origin.describe("markdown", resolver.root()))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                "./ripari.json",
+                "./ripari.json:4:50 (overrides[0])",
+                "./ripari.json:5:47 (overrides[1])",
+                "./i18n/fr/ripari.json",
+                "./i18n/fr/ripari.json:1:19 (overrides[0])",
+            ]
+        );
+
+        // The later `i18n/**` override wins over the `i18n/fr/**` one
+        let config = merge_layers(&layers);
+        assert!(config.overrides.is_none());
+        let rules = config.markdown.unwrap().rules;
+        assert_eq!(rules.smart_quotes.enabled, Some(true));
+        assert_eq!(rules.headings.enabled, Some(false));
+    }
+
+    #[test]
+    fn test_nested_configuration_wins_over_parent_overrides() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{
+                "root": true,
+                "overrides": [
+                    { "include": ["docs/**"], "markdown": { "rules": { "smart_quotes": { "enabled": false } } } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(
+            dir.join("docs/ripari.json"),
+            r#"{ "markdown": { "rules": { "smart_quotes": { "enabled": true } } } }"#,
+        )
+        .unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let config = resolver
+            .resolve_file(Path::new("docs/guide.md"), &EnvConsole::new(false))
+            .unwrap();
+        assert_eq!(
+            config.markdown.unwrap().rules.smart_quotes.enabled,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_rule_flags_take_precedence() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{
+                "root": true,
+                "overrides": [{ "markdown": { "rules": { "headings": { "enabled": true } } } }]
+            }"#,
+        )
+        .unwrap();
+
+        let mut rule_overrides = RuleOverrides::default();
+        let args = vec!["--skip".to_string(), "headings".to_string()];
+        assert!(rule_overrides.parse_flag(&args, &mut 0).unwrap());
+        let mut resolver = ConfigurationResolver::new(dir).with_rule_overrides(rule_overrides);
+        let console = EnvConsole::new(false);
+
+        let file = resolver.resolve_file(Path::new("a.md"), &console).unwrap();
+        assert_eq!(file.markdown.unwrap().rules.headings.enabled, Some(false));
+        let directory = resolver.resolve_directory(dir, &console).unwrap();
+        assert_eq!(
+            directory.markdown.unwrap().rules.headings.enabled,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_overrides_apply_per_file() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{
+                "root": true,
+                "overrides": [
+                    {
+                        "include": ["slides/**"],
+                        "markdown": { "rules": { "remove_horizontal_rules": { "enabled": false } } }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("slides")).unwrap();
+        fs::write(dir.join("slides/deck.md"), "---\n").unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let console = EnvConsole::new(false);
+        let slides = resolver
+            .resolve_file(&dir.join("slides/deck.md"), &console)
+            .unwrap();
+        let readme = resolver
+            .resolve_file(Path::new("README.md"), &console)
+            .unwrap();
+        assert_eq!(
+            slides
+                .markdown
+                .unwrap()
+                .rules
+                .remove_horizontal_rules
+                .enabled,
+            Some(false)
+        );
+        assert!(readme.markdown.is_none());
+    }
+
+    #[test]
+    fn test_extended_patterns_are_relative_to_the_extending_file() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("node_modules/@acme/ripari-config")).unwrap();
+        fs::write(
+            dir.join("node_modules/@acme/ripari-config/ripari.json"),
+            r#"{
+                "files": { "ignore": ["drafts/**"] },
+                "overrides": [
+                    {
+                        "include": ["slides/**"],
+                        "markdown": { "rules": { "remove_horizontal_rules": { "enabled": false } } }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("ripari.json"),
+            r#"{ "root": true, "extends": ["@acme/ripari-config"] }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("drafts")).unwrap();
+        fs::write(dir.join("drafts/post.md"), "Draft\n").unwrap();
+        fs::create_dir_all(dir.join("slides")).unwrap();
+        fs::write(dir.join("slides/deck.md"), "---\n").unwrap();
+
+        let mut resolver = ConfigurationResolver::new(dir);
+        let console = EnvConsole::new(false);
+        let draft = resolver
+            .select_file(Path::new("drafts/post.md"), &console)
+            .unwrap();
+        assert!(matches!(draft, Selection::IgnoredBy(_)));
+        let slides = resolver
+            .resolve_file(Path::new("slides/deck.md"), &console)
+            .unwrap();
+        assert_eq!(
+            slides
+                .markdown
+                .unwrap()
+                .rules
+                .remove_horizontal_rules
+                .enabled,
+            Some(false)
+        );
+    }
+}
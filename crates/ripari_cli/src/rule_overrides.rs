@@ -0,0 +1,339 @@
+use crate::diagnostics::CliDiagnostic;
+use crate::origin::{ConfigurationLayer, Origin};
+use serde_json::{json, Value};
+use simora_configuration::{
+    did_you_mean, find_rule, validate_value, RuleMetadata, Schema, ValueKind, CONFIGURATION_SCHEMA,
+    RULES,
+};
+
+/// Environment variables read by [RuleOverrides::from_env], mirroring the flags
+const ENVIRONMENT_FLAGS: &[(&str, &str)] = &[
+    ("RIPARI_RULE", "--rule"),
+    ("RIPARI_ONLY", "--only"),
+    ("RIPARI_SKIP", "--skip"),
+];
+
+/// Rule settings given with `--rule`, `--only` and `--skip`, or their `RIPARI_` environment
+/// variables. They take precedence over every configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct RuleOverrides {
+    layers: Vec<ConfigurationLayer>,
+}
+
+impl RuleOverrides {
+    /// Reads `RIPARI_RULE`, `RIPARI_ONLY` and `RIPARI_SKIP`, whose values are
+    /// comma-separated like those of the flags
+    pub fn from_env(
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, CliDiagnostic> {
+        let vars: Vec<(String, String)> = vars.into_iter().collect();
+        let mut overrides = Self::default();
+        for (variable, flag) in ENVIRONMENT_FLAGS {
+            if let Some((_, value)) = vars.iter().find(|(name, _)| name == variable) {
+                let origin = Origin::Environment(format!("{}={}", variable, value));
+                overrides.push(flag, value, origin)?;
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Consumes `args[*i]`, and the value following it, if it is a rule flag.
+    /// Returns whether it was one.
+    pub fn parse_flag(&mut self, args: &[String], i: &mut usize) -> Result<bool, CliDiagnostic> {
+        let arg = args[*i].as_str();
+        for flag in ["--rule", "--only", "--skip"] {
+            if let Some(value) = arg
+                .strip_prefix(flag)
+                .and_then(|rest| rest.strip_prefix('='))
+            {
+                self.push(flag, value, Origin::Flag(arg.to_string()))?;
+                return Ok(true);
+            }
+            if arg == flag {
+                let value = args
+                    .get(*i + 1)
+                    .ok_or_else(|| CliDiagnostic::error(format!("Missing value for `{}`", flag)))?;
+                *i += 1;
+                let origin = Origin::Flag(format!("{} {}", flag, value));
+                self.push(flag, value, origin)?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    pub fn layers(&self) -> &[ConfigurationLayer] {
+        &self.layers
+    }
+
+    fn push(&mut self, flag: &str, value: &str, origin: Origin) -> Result<(), CliDiagnostic> {
+        let mut settings = Vec::new();
+        if flag == "--rule" {
+            for item in rule_settings(value) {
+                settings.push(rule_setting(item)?);
+            }
+        } else {
+            let names = value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|name| lookup_rule(name).map(|rule| rule.name))
+                .collect::<Result<Vec<_>, _>>()?;
+            let only = flag == "--only";
+            // `--only` disables every rule it does not list
+            let toggled = RULES
+                .iter()
+                .filter(|rule| only || names.contains(&rule.name))
+                .map(|rule| {
+                    let enabled = only && names.contains(&rule.name);
+                    (rule.name.to_string(), json!({ "enabled": enabled }))
+                });
+            settings.push(Value::Object(toggled.collect()));
+        }
+
+        for rules in settings {
+            let configuration =
+                serde_json::from_value(json!({ "markdown": { "rules": rules } }))
+                    .map_err(|e| CliDiagnostic::error(format!("Invalid `{}`: {}", flag, e)))?;
+            self.layers.push(ConfigurationLayer {
+                origin: origin.clone(),
+                configuration,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Splits comma-separated rule settings, only at the commas followed by the
+/// `rule=` or `rule.option=` of a new setting, or ending the list, so that values may
+/// contain commas
+fn rule_settings(value: &str) -> Vec<&str> {
+    let starts_setting = |rest: &str| {
+        rest.trim().is_empty()
+            || rest.split_once('=').is_some_and(|(key, _)| {
+                let key = key.trim();
+                !key.is_empty()
+                    && key.split('.').all(|part| {
+                        !part.is_empty()
+                            && part.chars().all(|c| c == '_' || c.is_ascii_alphanumeric())
+                    })
+            })
+    };
+    let mut settings = Vec::new();
+    let mut start = 0;
+    for (comma, _) in value.match_indices(',') {
+        if starts_setting(&value[comma + 1..]) {
+            settings.push(&value[start..comma]);
+            start = comma + 1;
+        }
+    }
+    settings.push(&value[start..]);
+    settings
+        .into_iter()
+        .map(str::trim)
+        .filter(|setting| !setting.is_empty())
+        .collect()
+}
+
+/// Parses `rule=value` or `rule.option=value`, where `rule=value` sets `enabled`
+fn rule_setting(setting: &str) -> Result<Value, CliDiagnostic> {
+    let invalid = |problem: String| {
+        CliDiagnostic::error(format!("Invalid rule setting `{}`: {}", setting, problem))
+    };
+    let (key, value) = setting
+        .split_once('=')
+        .ok_or_else(|| invalid("expected `rule=value` or `rule.option=value`.".to_string()))?;
+    let (rule_name, option_name) = key.split_once('.').unwrap_or((key, "enabled"));
+    let rule = lookup_rule(rule_name)?;
+    let Some(option) = rule.find_option(option_name) else {
+        let mut problem = format!("unknown option `{}` for `{}`.", option_name, rule.name);
+        let options = rule.options.iter().map(|option| option.name);
+        if let Some(suggestion) = did_you_mean(option_name, options) {
+            problem.push_str(&format!(" Did you mean `{}`?", suggestion));
+        }
+        return Err(invalid(problem));
+    };
+
+    let is_toggle = matches!(
+        option.schema,
+        Schema::Value(ValueKind::Boolean | ValueKind::BooleanOrEnum(_))
+    );
+    let value = match (&option.schema, value) {
+        (_, "on" | "true") if is_toggle => Value::Bool(true),
+        (_, "off" | "false") if is_toggle => Value::Bool(false),
+        (Schema::Value(ValueKind::Boolean), _) => {
+            return Err(invalid(format!(
+                "expected `on`, `off`, `true` or `false`, found `{}`.",
+                value
+            )))
+        }
+        _ => Value::String(value.to_string()),
+    };
+    let setting = json!({ rule.name: { option.name: value } });
+    let configuration = json!({ "markdown": { "rules": setting.clone() } });
+    if let Some(diagnostic) = validate_value(&configuration, &CONFIGURATION_SCHEMA).first() {
+        return Err(invalid(diagnostic.message.clone()));
+    }
+    Ok(setting)
+}
+
+fn lookup_rule(name: &str) -> Result<&'static RuleMetadata, CliDiagnostic> {
+    find_rule(name).ok_or_else(|| {
+        let hint = match did_you_mean(name, RULES.iter().map(|rule| rule.name)) {
+            Some(suggestion) => format!("Did you mean `{}`?", suggestion),
+            None => {
+                let names: Vec<&str> = RULES.iter().map(|rule| rule.name).collect();
+                format!("Available rules are {}.", names.join(", "))
+            }
+        };
+        CliDiagnostic::error(format!("Unknown rule `{}`. {}", name, hint))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::origin::merge_layers;
+    use simora_configuration::StrategyOption;
+    use std::path::Path;
+
+    fn parse(args: &[&str]) -> Result<RuleOverrides, CliDiagnostic> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut overrides = RuleOverrides::default();
+        let mut i = 0;
+        while i < args.len() {
+            assert!(overrides.parse_flag(&args, &mut i)?);
+            i += 1;
+        }
+        Ok(overrides)
+    }
+
+    fn resolved_rules(overrides: &RuleOverrides) -> simora_configuration::RulesConfig {
+        merge_layers(overrides.layers()).markdown.unwrap().rules
+    }
+
+    #[test]
+    fn test_rule_flag() {
+        let overrides = parse(&[
+            "--rule",
+            "smart_quotes=off",
+            "--rule=punctuation.standardize_dashes=false,headings=on",
+        ])
+        .unwrap();
+        let rules = resolved_rules(&overrides);
+        assert_eq!(rules.smart_quotes.enabled, Some(false));
+        assert_eq!(
+            rules.punctuation.standardize_dashes,
+            Some(StrategyOption::Enabled(false))
+        );
+        assert_eq!(rules.punctuation.enabled, None);
+        assert_eq!(rules.headings.enabled, Some(true));
+
+        let overrides = parse(&["--rule", "punctuation.standardize_dashes=comma"]).unwrap();
+        assert_eq!(
+            resolved_rules(&overrides).punctuation.standardize_dashes,
+            Some(StrategyOption::Named("comma".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rule_settings_split_before_each_setting() {
+        assert_eq!(
+            rule_settings("headings=on, math.delimiters=dollars,"),
+            vec!["headings=on", "math.delimiters=dollars"]
+        );
+        assert_eq!(
+            rule_settings("emoji.body_text=a,b,c=d,e,headings=off"),
+            vec!["emoji.body_text=a,b", "c=d,e", "headings=off"]
+        );
+        assert_eq!(
+            parse(&["--rule", "headings=on,off"])
+                .unwrap_err()
+                .to_string(),
+            "Invalid rule setting `headings=on,off`: expected `on`, `off`, `true` or `false`, \
+             found `on,off`."
+        );
+    }
+
+    #[test]
+    fn test_only_and_skip() {
+        let rules = resolved_rules(&parse(&["--only", "headings,punctuation"]).unwrap());
+        assert_eq!(rules.headings.enabled, Some(true));
+        assert_eq!(rules.punctuation.enabled, Some(true));
+        assert_eq!(rules.smart_quotes.enabled, Some(false));
+        assert_eq!(rules.remove_horizontal_rules.enabled, Some(false));
+
+        let rules = resolved_rules(&parse(&["--skip=remove_horizontal_rules"]).unwrap());
+        assert_eq!(rules.remove_horizontal_rules.enabled, Some(false));
+        assert_eq!(rules.headings.enabled, None);
+    }
+
+    #[test]
+    fn test_later_flags_win() {
+        let rules = resolved_rules(&parse(&["--only=headings", "--rule=smart_quotes=on"]).unwrap());
+        assert_eq!(rules.smart_quotes.enabled, Some(true));
+        assert_eq!(rules.punctuation.enabled, Some(false));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let error = |args: &[&str]| parse(args).unwrap_err().to_string();
+        assert_eq!(
+            error(&["--rule", "smart_qoutes=off"]),
+            "Unknown rule `smart_qoutes`. Did you mean `smart_quotes`?"
+        );
+        assert_eq!(
+            error(&["--rule", "punctuation.standardize_dahses=off"]),
+            "Invalid rule setting `punctuation.standardize_dahses=off`: unknown option \
+             `standardize_dahses` for `punctuation`. Did you mean `standardize_dashes`?"
+        );
+        assert_eq!(
+            error(&["--rule", "headings=maybe"]),
+            "Invalid rule setting `headings=maybe`: expected `on`, `off`, `true` or `false`, \
+             found `maybe`."
+        );
+        assert_eq!(
+            error(&["--rule", "headings"]),
+            "Invalid rule setting `headings`: expected `rule=value` or `rule.option=value`."
+        );
+        assert_eq!(error(&["--skip"]), "Missing value for `--skip`");
+    }
+
+    #[test]
+    fn test_unrelated_flags_are_left_alone() {
+        let args = vec!["--write".to_string()];
+        let mut overrides = RuleOverrides::default();
+        assert!(!overrides.parse_flag(&args, &mut 0).unwrap());
+        assert!(overrides.layers().is_empty());
+    }
+
+    #[test]
+    fn test_from_env() {
+        let overrides = RuleOverrides::from_env(vec![
+            ("HOME".to_string(), "/root".to_string()),
+            ("RIPARI_SKIP".to_string(), "headings".to_string()),
+            ("RIPARI_RULE".to_string(), "smart_quotes=off".to_string()),
+        ])
+        .unwrap();
+        let origins: Vec<String> = overrides
+            .layers()
+            .iter()
+            .map(|layer| layer.origin.describe("markdown", Path::new("/")))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                "environment variable `RIPARI_RULE=smart_quotes=off`",
+                "environment variable `RIPARI_SKIP=headings`"
+            ]
+        );
+        let rules = resolved_rules(&overrides);
+        assert_eq!(rules.headings.enabled, Some(false));
+        assert_eq!(rules.smart_quotes.enabled, Some(false));
+
+        let error =
+            RuleOverrides::from_env(vec![("RIPARI_ONLY".to_string(), "tables".to_string())])
+                .unwrap_err();
+        assert!(error.to_string().starts_with("Unknown rule `tables`."));
+    }
+}
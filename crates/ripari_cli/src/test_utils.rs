@@ -0,0 +1,39 @@
+use crate::console::Console;
+use std::cell::RefCell;
+use tempfile::TempDir;
+
+/// A directory for a test's files, removed when dropped. Its path is canonical, like
+/// those configuration files are looked up from.
+pub(crate) fn temp_dir() -> TempDir {
+    let parent = std::env::temp_dir().canonicalize().unwrap();
+    tempfile::Builder::new()
+        .prefix("ripari_")
+        .tempdir_in(parent)
+        .unwrap()
+}
+
+/// A console recording what commands print, errors prefixed with `ERROR: `
+#[derive(Default)]
+pub(crate) struct MockConsole {
+    logs: RefCell<Vec<String>>,
+}
+
+impl Console for MockConsole {
+    fn log(&self, message: &str) {
+        self.logs.borrow_mut().push(message.to_string());
+    }
+
+    fn error(&self, message: &str) {
+        self.logs.borrow_mut().push(format!("ERROR: {}", message));
+    }
+}
+
+impl MockConsole {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn get_logs(&self) -> Vec<String> {
+        self.logs.borrow().clone()
+    }
+}
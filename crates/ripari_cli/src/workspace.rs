@@ -1,16 +1,20 @@
+use crate::console::Console;
 use crate::diagnostics::CliDiagnostic;
-use simora_configuration::{
-    global_config::{initialize_global_config, GlobalConfig},
-    Merge, PartialMarkdownFormatterConfiguration,
-};
-use std::fs;
+use crate::resolver::ConfigurationResolver;
+use simora_configuration::PartialMarkdownFormatterConfiguration;
 use std::path::PathBuf;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Workspace {
     root: PathBuf,
 }
 
+impl Default for Workspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Workspace {
     pub fn new() -> Self {
         Workspace {
@@ -18,8 +22,9 @@ impl Workspace {
         }
     }
 
-    pub fn default() -> Self {
-        Self::new()
+    /// A workspace rooted at `root` rather than the current directory
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Workspace { root: root.into() }
     }
 
     pub fn root(&self) -> &PathBuf {
@@ -31,70 +36,9 @@ impl Workspace {
     /// to least specific (root directory)
     pub fn find_configurations(
         &self,
+        console: &impl Console,
     ) -> Result<Vec<PartialMarkdownFormatterConfiguration>, CliDiagnostic> {
-        let mut configs = Vec::new();
-        let mut current_dir = self
-            .root
-            .canonicalize()
-            .map_err(|e| CliDiagnostic::error(format!("Failed to canonicalize path: {}", e)))?;
-
-        loop {
-            let config_path = current_dir.join("ripari.json");
-            if config_path.exists() {
-                let content = fs::read_to_string(&config_path).map_err(|e| {
-                    CliDiagnostic::error(format!("Failed to read config file: {}", e))
-                })?;
-
-                let config: PartialMarkdownFormatterConfiguration = serde_json::from_str(&content)
-                    .map_err(|e| {
-                        CliDiagnostic::error(format!("Failed to parse config file: {}", e))
-                    })?;
-
-                let is_root = config.root;
-                configs.push(config);
-
-                if is_root {
-                    break;
-                }
-            }
-
-            if !current_dir.pop() {
-                break;
-            }
-        }
-
-        Ok(configs)
-    }
-
-    /// Load and merge all configurations from the current directory up to the root
-    pub fn load_merged_configuration(
-        &self,
-    ) -> Result<PartialMarkdownFormatterConfiguration, CliDiagnostic> {
-        let configs = self.find_configurations()?;
-        if configs.is_empty() {
-            let default_config = PartialMarkdownFormatterConfiguration::default();
-            initialize_global_config(GlobalConfig {
-                markdown: default_config.markdown.clone(),
-                files: default_config.files.clone(),
-                vcs: default_config.vcs.clone(),
-            });
-            return Ok(default_config);
-        }
-
-        let mut merged = configs.last().unwrap().clone(); // Start with the most general config
-        for config in configs.iter().rev().skip(1) {
-            // Skip the last one since we started with it
-            merged.merge_with(config.clone());
-        }
-
-        // Initialize global config with merged configuration
-        initialize_global_config(GlobalConfig {
-            markdown: merged.markdown.clone(),
-            files: merged.files.clone(),
-            vcs: merged.vcs.clone(),
-        });
-
-        Ok(merged)
+        ConfigurationResolver::new(&self.root).find_configurations(&self.root, console)
     }
 }
 
@@ -0,0 +1,194 @@
+use crate::location::{find_json_path, Location};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// A file format configuration can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigurationFormat {
+    Json,
+    Toml,
+    Yaml,
+    /// The `"ripari"` key of a `package.json`
+    PackageJson,
+}
+
+/// The configuration files looked up in each directory, in order of precedence.
+/// Only the first one found is used.
+pub const CONFIGURATION_FILES: &[(&str, ConfigurationFormat)] = &[
+    ("ripari.json", ConfigurationFormat::Json),
+    ("ripari.toml", ConfigurationFormat::Toml),
+    ("ripari.yaml", ConfigurationFormat::Yaml),
+    ("ripari.yml", ConfigurationFormat::Yaml),
+    ("package.json", ConfigurationFormat::PackageJson),
+];
+
+/// The key holding the configuration in a `package.json`
+const PACKAGE_JSON_KEY: &str = "ripari";
+
+/// A syntax error, with the message and the position of the offending character
+pub(crate) struct SyntaxError {
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl ConfigurationFormat {
+    /// The format of a configuration file, from its name. Unknown extensions are read as JSON.
+    pub fn from_path(path: &Path) -> Self {
+        if path.file_name().is_some_and(|name| name == "package.json") {
+            return Self::PackageJson;
+        }
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::Toml,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+
+    /// Whether `source` holds configuration, `package.json` files only do with a `"ripari"` key
+    pub fn has_configuration(self, source: &str) -> bool {
+        match self {
+            Self::PackageJson => serde_json::from_str::<Value>(source)
+                .is_ok_and(|package| package.get(PACKAGE_JSON_KEY).is_some()),
+            _ => true,
+        }
+    }
+
+    /// Finds where a configuration path such as `markdown.rules` is declared in `source`.
+    /// Only JSON sources are searched.
+    pub fn find_path(self, source: &str, path: &str) -> Option<Location> {
+        match self {
+            Self::Json => find_json_path(source, path),
+            Self::PackageJson if path.is_empty() => find_json_path(source, PACKAGE_JSON_KEY),
+            Self::PackageJson => find_json_path(source, &format!("{}.{}", PACKAGE_JSON_KEY, path)),
+            Self::Toml | Self::Yaml => None,
+        }
+    }
+
+    /// Parses `source` into the JSON data model the schema is checked against
+    pub(crate) fn parse(self, source: &str) -> Result<Value, SyntaxError> {
+        let value = match self {
+            Self::Json => serde_json::from_str(source).map_err(json_error)?,
+            Self::PackageJson => {
+                let mut package: Value = serde_json::from_str(source).map_err(json_error)?;
+                package
+                    .get_mut(PACKAGE_JSON_KEY)
+                    .map(Value::take)
+                    .unwrap_or_default()
+            }
+            Self::Toml => toml::from_str(source).map_err(|e| SyntaxError {
+                message: e.message().to_string(),
+                location: e.span().map(|span| offset_location(source, span.start)),
+            })?,
+            Self::Yaml => serde_yaml::from_str(source).map_err(|e| SyntaxError {
+                // serde_yaml appends the position, which is reported separately
+                message: strip_position(&e.to_string(), " at line "),
+                location: e.location().map(|location| Location {
+                    line: location.line(),
+                    column: location.column(),
+                }),
+            })?,
+        };
+        // An empty file is an empty configuration
+        Ok(match value {
+            Value::Null => Value::Object(Map::new()),
+            value => value,
+        })
+    }
+}
+
+fn json_error(error: serde_json::Error) -> SyntaxError {
+    SyntaxError {
+        // serde_json appends the position, which is reported separately
+        message: strip_position(&error.to_string(), " at line "),
+        location: Some(Location {
+            line: error.line(),
+            column: error.column(),
+        }),
+    }
+}
+
+fn strip_position(message: &str, marker: &str) -> String {
+    match message.rfind(marker) {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// The 1-based line and column of a byte offset
+fn offset_location(source: &str, offset: usize) -> Location {
+    let before = &source[..offset.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Location {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_configuration_as;
+
+    #[test]
+    fn test_from_path() {
+        let format = |path: &str| ConfigurationFormat::from_path(Path::new(path));
+        assert_eq!(format("docs/ripari.toml"), ConfigurationFormat::Toml);
+        assert_eq!(format("ripari.yml"), ConfigurationFormat::Yaml);
+        assert_eq!(format("package.json"), ConfigurationFormat::PackageJson);
+        assert_eq!(format("base.json"), ConfigurationFormat::Json);
+    }
+
+    #[test]
+    fn test_toml_and_yaml_match_json() {
+        let parse = |source: &str, format| {
+            let parsed = parse_configuration_as(source, format).unwrap();
+            serde_json::to_value(parsed.configuration).unwrap()
+        };
+        let json = parse(
+            r#"{ "markdown": { "rules": { "headings": { "enabled": false } } } }"#,
+            ConfigurationFormat::Json,
+        );
+        let toml = parse(
+            "[markdown.rules.headings]\nenabled = false\n",
+            ConfigurationFormat::Toml,
+        );
+        let yaml = parse(
+            "markdown:\n  rules:\n    headings:\n      enabled: false\n",
+            ConfigurationFormat::Yaml,
+        );
+        assert_eq!(toml, json);
+        assert_eq!(yaml, json);
+    }
+
+    #[test]
+    fn test_syntax_errors_have_locations() {
+        let error = |source: &str, format| {
+            let diagnostics = parse_configuration_as(source, format).unwrap_err();
+            diagnostics[0].location.map(|location| location.to_string())
+        };
+        assert_eq!(
+            error("[markdown]\nenabled = \n", ConfigurationFormat::Toml),
+            Some("2:11".to_string())
+        );
+        assert_eq!(
+            error("markdown:\n  enabled: [true\n", ConfigurationFormat::Yaml),
+            Some("3:1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_package_json_key() {
+        let source = r#"{
+  "name": "docs",
+  "ripari": { "markdown": { "rules": { "smart_qoutes": {} } } }
+}"#;
+        let format = ConfigurationFormat::PackageJson;
+        assert!(format.has_configuration(source));
+        assert!(!format.has_configuration(r#"{ "name": "docs" }"#));
+
+        let diagnostics = parse_configuration_as(source, format).unwrap_err();
+        assert_eq!(diagnostics[0].location.unwrap().to_string(), "3:40");
+        let parsed = parse_configuration_as(r#"{ "name": "docs" }"#, format).unwrap();
+        assert!(parsed.configuration.markdown.is_none());
+    }
+}
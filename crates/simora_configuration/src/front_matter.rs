@@ -0,0 +1,124 @@
+use crate::location::Location;
+use crate::schema::{Field, Schema};
+use crate::validation::{validate_value, ConfigurationDiagnostic};
+use crate::RulesConfig;
+use serde_json::Value;
+
+/// The key of a document's front matter holding its rule settings
+pub const FRONT_MATTER_KEY: &str = "ripari";
+
+/// The schema of the part of front matter ripari reads
+static FRONT_MATTER_SCHEMA: Schema = Schema::Object(&[Field::new(
+    FRONT_MATTER_KEY,
+    Schema::Rules,
+    "Rule settings for this document",
+)]);
+
+/// Reads the rule settings of the `ripari:` key of a document's YAML front matter.
+/// Front matter that is missing, not YAML or without the key yields `None`, as it
+/// belongs to other tools.
+pub fn parse_front_matter(
+    content: &str,
+) -> Result<Option<RulesConfig>, Vec<ConfigurationDiagnostic>> {
+    let Some(yaml) = front_matter(content) else {
+        return Ok(None);
+    };
+    let Ok(Value::Object(mut front_matter)) = serde_yaml::from_str::<Value>(yaml) else {
+        return Ok(None);
+    };
+    let Some(rules) = front_matter.remove(FRONT_MATTER_KEY) else {
+        return Ok(None);
+    };
+
+    // Diagnostics point at the `ripari:` line, the YAML starting on the second line
+    let location = yaml
+        .lines()
+        .position(|line| {
+            line.strip_prefix(FRONT_MATTER_KEY)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|index| Location {
+            line: index + 2,
+            column: 1,
+        });
+    let rules = match rules {
+        Value::Null => Value::Object(Default::default()),
+        rules => rules,
+    };
+    let mut wrapped = serde_json::Map::new();
+    wrapped.insert(FRONT_MATTER_KEY.to_string(), rules.clone());
+    let mut diagnostics = validate_value(&Value::Object(wrapped), &FRONT_MATTER_SCHEMA);
+    for diagnostic in &mut diagnostics {
+        diagnostic.location = location;
+    }
+    if diagnostics.iter().any(ConfigurationDiagnostic::is_error) {
+        return Err(diagnostics);
+    }
+
+    serde_json::from_value(rules).map(Some).map_err(|e| {
+        vec![ConfigurationDiagnostic::error(
+            FRONT_MATTER_KEY,
+            e.to_string(),
+        )]
+    })
+}
+
+/// The YAML between the leading `---` lines of `content`
+fn front_matter(content: &str) -> Option<&str> {
+    let rest = content
+        .strip_prefix("---\r\n")
+        .or_else(|| content.strip_prefix("---\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some(&rest[..offset]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_rules_from_front_matter() {
+        let content = "---\ntitle: Post\nripari:\n  punctuation:\n    standardize_dashes: false\n---\n\n# Post\n";
+        let rules = parse_front_matter(content).unwrap().unwrap();
+        assert_eq!(
+            rules.punctuation.standardize_dashes,
+            Some(crate::StrategyOption::Enabled(false))
+        );
+        assert_eq!(rules.punctuation.enabled, None);
+
+        let flow = "---\nripari: { smart_quotes: { enabled: false } }\n---\n";
+        let rules = parse_front_matter(flow).unwrap().unwrap();
+        assert_eq!(rules.smart_quotes.enabled, Some(false));
+    }
+
+    #[test]
+    fn test_ignores_other_front_matter() {
+        assert!(parse_front_matter("# Title\n").unwrap().is_none());
+        assert!(parse_front_matter("---\ntitle: Post\n---\n")
+            .unwrap()
+            .is_none());
+        assert!(parse_front_matter("---\n: not yaml [\n---\n")
+            .unwrap()
+            .is_none());
+        assert!(parse_front_matter("---\nripari: {}\n").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reports_invalid_rules() {
+        let content = "---\ntitle: Post\nripari:\n  smart_qoutes: {}\n---\n";
+        let diagnostics = parse_front_matter(content).unwrap_err();
+        assert_eq!(diagnostics[0].path, "ripari.smart_qoutes");
+        assert_eq!(diagnostics[0].location.unwrap().to_string(), "3:1");
+
+        // Keys merely starting with `ripari` are not the one reported
+        let content = "---\nripari_notes: draft\nripari:\n  smart_qoutes: {}\n---\n";
+        let diagnostics = parse_front_matter(content).unwrap_err();
+        assert_eq!(diagnostics[0].location.unwrap().to_string(), "3:1");
+    }
+}
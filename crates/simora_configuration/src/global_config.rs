@@ -1,11 +1,7 @@
 // crates/simora_configuration/src/global_config.rs
 
-use std::fs;
+use crate::types::{MarkdownFormatterConfig, PartialFilesConfiguration, PartialVcsConfiguration};
 use serde::{Deserialize, Serialize}; // Add Serialize and Deserialize imports
-use crate::types::{
-    MarkdownFormatterConfig, PartialFilesConfiguration,
-    PartialVcsConfiguration,
-};
 
 // Define a struct for the global configuration
 #[derive(Serialize, Deserialize)] // Derive Serialize and Deserialize
@@ -27,7 +23,7 @@ pub fn initialize_global_config(config: GlobalConfig) {
 
 /// Gets a reference to the global configuration
 pub fn get_global_config() -> Option<&'static GlobalConfig> {
-    unsafe { GLOBAL_CONFIG.as_ref() }
+    unsafe { (*std::ptr::addr_of!(GLOBAL_CONFIG)).as_ref() }
 }
 
 /// Gets the markdown formatter configuration from global config
@@ -1,42 +1,71 @@
-
+mod formats;
+mod front_matter;
+pub mod global_config;
+mod location;
+mod overrides;
+mod presets;
+mod registry;
 mod rules;
+mod schema;
+mod suggestion;
 mod types;
-pub mod global_config;
+mod validation;
+pub use formats::{ConfigurationFormat, CONFIGURATION_FILES};
+pub use front_matter::{parse_front_matter, FRONT_MATTER_KEY};
 pub use global_config::initialize_global_config; // Export the new function
+pub use location::{find_json_path, Location};
+pub use presets::{find_preset, Preset, PRESETS, PRESET_NAMES};
+pub use registry::{find_markdownlint_equivalent, find_rule, Equivalent, RuleMetadata, RULES};
 pub use rules::{
-    HeadingsConfig, HorizontalRulesConfig, PunctuationConfig, RulesConfig, SmartQuotesConfig,
+    EmojiConfig, HeadingsConfig, HorizontalRulesConfig, InvisibleCharactersConfig, MathConfig,
+    PunctuationConfig, ReasoningBlocksConfig, RulesConfig, SmartQuotesConfig, StrategyOption,
 };
+pub use schema::{Field, Schema, ValueKind, CONFIGURATION_SCHEMA};
+pub use suggestion::did_you_mean;
 pub use types::{
     MarkdownFormatterConfig, PartialFilesConfiguration, PartialMarkdownFormatterConfiguration,
-    PartialVcsConfiguration,
+    PartialOverrideConfiguration, PartialVcsConfiguration,
+};
+pub use validation::{
+    parse_configuration, parse_configuration_as, validate_value, ConfigurationDiagnostic,
+    ParsedConfiguration, Severity,
 };
-
 
 /// Trait for merging configurations
 pub trait Merge {
     fn merge_with(&mut self, other: Self);
 }
 
+impl Merge for MarkdownFormatterConfig {
+    fn merge_with(&mut self, other: Self) {
+        if other.enabled.is_some() {
+            self.enabled = other.enabled;
+        }
+        self.rules.merge_with(other.rules);
+        if other.end_of_line.is_some() {
+            self.end_of_line = other.end_of_line;
+        }
+        if other.insert_final_newline.is_some() {
+            self.insert_final_newline = other.insert_final_newline;
+        }
+        if other.trim_trailing_whitespace.is_some() {
+            self.trim_trailing_whitespace = other.trim_trailing_whitespace;
+        }
+        if other.indent_style.is_some() {
+            self.indent_style = other.indent_style;
+        }
+        if other.max_line_length.is_some() {
+            self.max_line_length = other.max_line_length;
+        }
+    }
+}
+
 impl Merge for PartialMarkdownFormatterConfiguration {
     fn merge_with(&mut self, other: Self) {
         if let Some(markdown) = other.markdown {
             self.markdown = Some(match self.markdown.take() {
                 Some(mut current) => {
-                    current.enabled |= markdown.enabled;
-
-                    if markdown.rules.smart_quotes.enabled {
-                        current.rules.smart_quotes = markdown.rules.smart_quotes;
-                    }
-                    if markdown.rules.headings.enabled {
-                        current.rules.headings = markdown.rules.headings;
-                    }
-                    if markdown.rules.remove_horizontal_rules.enabled {
-                        current.rules.remove_horizontal_rules =
-                            markdown.rules.remove_horizontal_rules;
-                    }
-                    if markdown.rules.punctuation.enabled {
-                        current.rules.punctuation = markdown.rules.punctuation;
-                    }
+                    current.merge_with(markdown);
                     current
                 }
                 None => markdown,
@@ -51,6 +80,9 @@ impl Merge for PartialMarkdownFormatterConfiguration {
                     if let Some(include) = files.include {
                         current.include = Some(include);
                     }
+                    if let Some(case_sensitive) = files.case_sensitive {
+                        current.case_sensitive = Some(case_sensitive);
+                    }
                     current
                 }
                 None => files,
@@ -73,6 +105,12 @@ impl Merge for PartialMarkdownFormatterConfiguration {
                 None => vcs,
             });
         }
+        if let Some(overrides) = other.overrides {
+            // Overrides accumulate, so those of more specific files are applied last
+            self.overrides
+                .get_or_insert_with(Vec::new)
+                .extend(overrides);
+        }
     }
 }
 
@@ -97,24 +135,28 @@ mod tests {
         // Create a partial configuration that only specifies smart quotes
         let partial_config = PartialMarkdownFormatterConfiguration {
             markdown: Some(MarkdownFormatterConfig {
-                enabled: true,
+                enabled: Some(true),
                 rules: RulesConfig {
-                    smart_quotes: SmartQuotesConfig { enabled: true },
+                    smart_quotes: SmartQuotesConfig {
+                        enabled: Some(true),
+                        ..Default::default()
+                    },
                     ..Default::default() // All other rules should remain default (false)
                 },
+                ..Default::default()
             }),
             ..Default::default()
         };
 
         // Verify only smart quotes are enabled
         let rules = &partial_config.markdown.unwrap().rules;
-        assert!(rules.smart_quotes.enabled);
-        assert!(!rules.headings.enabled);
-        assert!(!rules.headings.remove_emphasis);
-        assert!(!rules.remove_horizontal_rules.enabled);
-        assert!(!rules.punctuation.enabled);
-        assert!(!rules.punctuation.standardize_dashes);
-        assert!(!rules.punctuation.standardize_ellipsis);
+        assert_eq!(rules.smart_quotes.enabled, Some(true));
+        assert!(!rules.headings.enabled.unwrap_or_default());
+        assert!(!rules.headings.remove_emphasis.unwrap_or_default());
+        assert!(!rules.remove_horizontal_rules.enabled.unwrap_or_default());
+        assert!(!rules.punctuation.enabled.unwrap_or_default());
+        assert_eq!(rules.punctuation.standardize_dashes, None);
+        assert!(!rules.punctuation.standardize_ellipsis.unwrap_or_default());
     }
 
     #[test]
@@ -125,11 +167,15 @@ mod tests {
         // Override with partial config that only sets smart quotes
         let override_config = PartialMarkdownFormatterConfiguration {
             markdown: Some(MarkdownFormatterConfig {
-                enabled: true,
+                enabled: Some(true),
                 rules: RulesConfig {
-                    smart_quotes: SmartQuotesConfig { enabled: true },
+                    smart_quotes: SmartQuotesConfig {
+                        enabled: Some(true),
+                        ..Default::default()
+                    },
                     ..Default::default()
                 },
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -138,12 +184,16 @@ mod tests {
 
         // Check that only smart quotes were enabled, everything else preserved defaults
         let markdown = base.markdown.unwrap();
-        assert!(markdown.enabled);
-        assert!(markdown.rules.smart_quotes.enabled);
-        assert!(!markdown.rules.headings.enabled);
-        assert!(!markdown.rules.headings.remove_emphasis);
-        assert!(!markdown.rules.remove_horizontal_rules.enabled);
-        assert!(!markdown.rules.punctuation.enabled);
+        assert_eq!(markdown.enabled, Some(true));
+        assert_eq!(markdown.rules.smart_quotes.enabled, Some(true));
+        assert!(!markdown.rules.headings.enabled.unwrap_or_default());
+        assert!(!markdown.rules.headings.remove_emphasis.unwrap_or_default());
+        assert!(!markdown
+            .rules
+            .remove_horizontal_rules
+            .enabled
+            .unwrap_or_default());
+        assert!(!markdown.rules.punctuation.enabled.unwrap_or_default());
     }
 
     #[test]
@@ -151,11 +201,15 @@ mod tests {
         // Root config with base settings
         let mut base = PartialMarkdownFormatterConfiguration {
             markdown: Some(MarkdownFormatterConfig {
-                enabled: true,
+                enabled: Some(true),
                 rules: RulesConfig {
-                    smart_quotes: SmartQuotesConfig { enabled: true },
+                    smart_quotes: SmartQuotesConfig {
+                        enabled: Some(true),
+                        ..Default::default()
+                    },
                     ..Default::default()
                 },
+                ..Default::default()
             }),
             root: true,
             ..Default::default()
@@ -164,14 +218,15 @@ mod tests {
         // Project config overriding some settings
         let project_config = PartialMarkdownFormatterConfiguration {
             markdown: Some(MarkdownFormatterConfig {
-                enabled: true,
+                enabled: Some(true),
                 rules: RulesConfig {
                     headings: HeadingsConfig {
-                        enabled: true,
-                        remove_emphasis: true,
+                        enabled: Some(true),
+                        remove_emphasis: Some(true),
                     },
                     ..Default::default()
                 },
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -179,15 +234,63 @@ mod tests {
         base.merge_with(project_config);
 
         let markdown = base.markdown.unwrap();
-        assert!(markdown.enabled);
+        assert_eq!(markdown.enabled, Some(true));
         // Smart quotes from root config preserved
-        assert!(markdown.rules.smart_quotes.enabled);
+        assert_eq!(markdown.rules.smart_quotes.enabled, Some(true));
         // Headings from project config applied
-        assert!(markdown.rules.headings.enabled);
-        assert!(markdown.rules.headings.remove_emphasis);
+        assert_eq!(markdown.rules.headings.enabled, Some(true));
+        assert_eq!(markdown.rules.headings.remove_emphasis, Some(true));
         // Other rules still default
-        assert!(!markdown.rules.remove_horizontal_rules.enabled);
-        assert!(!markdown.rules.punctuation.enabled);
+        assert!(!markdown
+            .rules
+            .remove_horizontal_rules
+            .enabled
+            .unwrap_or_default());
+        assert!(!markdown.rules.punctuation.enabled.unwrap_or_default());
+    }
+
+    #[test]
+    fn test_merge_can_disable_rule() {
+        let mut base = PartialMarkdownFormatterConfiguration {
+            markdown: Some(MarkdownFormatterConfig {
+                enabled: Some(true),
+                rules: RulesConfig {
+                    punctuation: PunctuationConfig {
+                        enabled: Some(true),
+                        standardize_dashes: Some(StrategyOption::Enabled(true)),
+                        standardize_ellipsis: Some(true),
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let override_config = PartialMarkdownFormatterConfiguration {
+            markdown: Some(MarkdownFormatterConfig {
+                rules: RulesConfig {
+                    punctuation: PunctuationConfig {
+                        standardize_dashes: Some(StrategyOption::Enabled(false)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        base.merge_with(override_config);
+
+        let punctuation = base.markdown.unwrap().rules.punctuation;
+        // Explicit `false` overrides, unset options are inherited
+        assert_eq!(punctuation.enabled, Some(true));
+        assert_eq!(
+            punctuation.standardize_dashes,
+            Some(StrategyOption::Enabled(false))
+        );
+        assert_eq!(punctuation.standardize_ellipsis, Some(true));
     }
 
     #[test]
@@ -196,6 +299,7 @@ mod tests {
             files: Some(PartialFilesConfiguration {
                 ignore: Some(vec!["**/node_modules/**".to_string()]),
                 include: None,
+                case_sensitive: None,
             }),
             ..Default::default()
         };
@@ -204,6 +308,7 @@ mod tests {
             files: Some(PartialFilesConfiguration {
                 ignore: None,
                 include: Some(vec!["**/*.md".to_string()]),
+                case_sensitive: Some(false),
             }),
             ..Default::default()
         };
@@ -215,6 +320,7 @@ mod tests {
         assert_eq!(files.ignore.unwrap(), vec!["**/node_modules/**"]);
         // New include patterns added
         assert_eq!(files.include.unwrap(), vec!["**/*.md"]);
+        assert_eq!(files.case_sensitive, Some(false));
     }
 
     #[test]
@@ -247,34 +353,71 @@ mod tests {
         assert_eq!(vcs.use_ignore_file, Some(true));
     }
 
+    #[test]
+    fn test_overrides_merge_appends() {
+        let override_for = |pattern: &str| PartialOverrideConfiguration {
+            include: Some(vec![pattern.to_string()]),
+            ..Default::default()
+        };
+        let mut base = PartialMarkdownFormatterConfiguration {
+            overrides: Some(vec![override_for("slides/**")]),
+            ..Default::default()
+        };
+        base.merge_with(PartialMarkdownFormatterConfiguration {
+            overrides: Some(vec![override_for("i18n/fr/**")]),
+            ..Default::default()
+        });
+        base.merge_with(PartialMarkdownFormatterConfiguration::default());
+
+        let includes: Vec<_> = base
+            .overrides
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.include.unwrap()[0].clone())
+            .collect();
+        assert_eq!(includes, vec!["slides/**", "i18n/fr/**"]);
+    }
+
     #[test]
     fn test_configuration_serialization() {
         let config = PartialMarkdownFormatterConfiguration {
             markdown: Some(MarkdownFormatterConfig {
-                enabled: true,
+                enabled: Some(true),
                 rules: RulesConfig {
-                    smart_quotes: SmartQuotesConfig { enabled: true },
+                    smart_quotes: SmartQuotesConfig {
+                        enabled: Some(true),
+                        ..Default::default()
+                    },
                     headings: HeadingsConfig {
-                        enabled: true,
-                        remove_emphasis: true,
+                        enabled: Some(true),
+                        remove_emphasis: Some(true),
+                    },
+                    remove_horizontal_rules: HorizontalRulesConfig {
+                        enabled: Some(true),
+                        retain_frontmatter_wrappers: Some(true),
                     },
-                    remove_horizontal_rules: HorizontalRulesConfig { enabled: true,  retain_frontmatter_wrappers: true },
                     punctuation: PunctuationConfig {
-                        enabled: true,
-                        standardize_dashes: true,
-                        standardize_ellipsis: true,
+                        enabled: Some(true),
+                        standardize_dashes: Some(StrategyOption::Enabled(true)),
+                        standardize_ellipsis: Some(true),
                     },
+                    ..Default::default()
                 },
+                ..Default::default()
             }),
             files: Some(PartialFilesConfiguration {
                 ignore: Some(vec!["**/node_modules/**".to_string()]),
                 include: Some(vec!["**/*.md".to_string()]),
+                case_sensitive: None,
             }),
             vcs: Some(PartialVcsConfiguration {
                 enabled: Some(true),
                 client_kind: Some("git".to_string()),
                 use_ignore_file: Some(true),
             }),
+            extends: None,
+            preset: None,
+            overrides: None,
             root: false,
         };
 
@@ -0,0 +1,194 @@
+use std::fmt;
+
+/// A 1-based line and column in a configuration source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+enum Frame {
+    Object { key: Option<String> },
+    Array { index: usize },
+}
+
+/// Finds where a JSON path (e.g. `markdown.rules.smart_quotes` or `files.ignore[2]`)
+/// is declared in `source`. Object members resolve to their key, array items to their value.
+pub fn find_json_path(source: &str, path: &str) -> Option<Location> {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut expecting_key = false;
+    let mut line = 1;
+    let mut column = 1;
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let location = Location { line, column };
+        advance(c, &mut line, &mut column);
+
+        match c {
+            '{' | '[' => {
+                if !matches!(stack.last(), Some(Frame::Object { .. }))
+                    && current_path(&stack) == path
+                {
+                    return Some(location);
+                }
+                if c == '{' {
+                    stack.push(Frame::Object { key: None });
+                    expecting_key = true;
+                } else {
+                    stack.push(Frame::Array { index: 0 });
+                }
+            }
+            '}' | ']' => {
+                stack.pop();
+                expecting_key = false;
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object { key }) => {
+                    *key = None;
+                    expecting_key = true;
+                }
+                Some(Frame::Array { index }) => *index += 1,
+                None => {}
+            },
+            '"' => {
+                let mut value = String::new();
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    advance(c, &mut line, &mut column);
+                    if escaped {
+                        value.push(c);
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == '"' {
+                        break;
+                    } else {
+                        value.push(c);
+                    }
+                }
+                if expecting_key {
+                    if let Some(Frame::Object { key }) = stack.last_mut() {
+                        *key = Some(value);
+                    }
+                    expecting_key = false;
+                    if current_path(&stack) == path {
+                        return Some(location);
+                    }
+                } else if matches!(stack.last(), Some(Frame::Array { .. }))
+                    && current_path(&stack) == path
+                {
+                    return Some(location);
+                }
+            }
+            c if c.is_whitespace() || c == ':' => {}
+            _ => {
+                // Scalar literal: numbers, `true`, `false` and `null`
+                if matches!(stack.last(), Some(Frame::Array { .. })) && current_path(&stack) == path
+                {
+                    return Some(location);
+                }
+                while let Some(&next) = chars.peek() {
+                    if matches!(next, ',' | '}' | ']') || next.is_whitespace() {
+                        break;
+                    }
+                    advance(next, &mut line, &mut column);
+                    chars.next();
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn advance(c: char, line: &mut usize, column: &mut usize) {
+    if c == '\n' {
+        *line += 1;
+        *column = 1;
+    } else {
+        *column += 1;
+    }
+}
+
+fn current_path(stack: &[Frame]) -> String {
+    let mut path = String::new();
+    for frame in stack {
+        match frame {
+            Frame::Object { key: Some(key) } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Frame::Object { key: None } => break,
+            Frame::Array { index } => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"{
+  "markdown": {
+    "rules": {
+      "smart_qoutes": { "enabled": true }
+    }
+  },
+  "files": { "ignore": ["a/**", "b/**", "{c}"] }
+}"#;
+
+    #[test]
+    fn test_finds_nested_key() {
+        assert_eq!(
+            find_json_path(SOURCE, "markdown.rules.smart_qoutes"),
+            Some(Location { line: 4, column: 7 })
+        );
+        assert_eq!(
+            find_json_path(SOURCE, "markdown.rules.smart_qoutes.enabled"),
+            Some(Location {
+                line: 4,
+                column: 25
+            })
+        );
+    }
+
+    #[test]
+    fn test_finds_array_item() {
+        assert_eq!(
+            find_json_path(SOURCE, "files.ignore[2]"),
+            Some(Location {
+                line: 7,
+                column: 41
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_path() {
+        assert_eq!(find_json_path(SOURCE, "markdown.rules.headings"), None);
+        assert_eq!(find_json_path(SOURCE, "files.ignore[3]"), None);
+    }
+
+    #[test]
+    fn test_does_not_confuse_values_with_keys() {
+        let source = r#"{ "client_kind": "root", "root": true }"#;
+        assert_eq!(
+            find_json_path(source, "root"),
+            Some(Location {
+                line: 1,
+                column: 26
+            })
+        );
+    }
+}
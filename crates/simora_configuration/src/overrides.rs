@@ -0,0 +1,84 @@
+use crate::PartialOverrideConfiguration;
+use simora_glob::{Glob, GlobOptions};
+use std::path::Path;
+
+impl PartialOverrideConfiguration {
+    /// Whether the override applies to `path`, relative to the directory of the
+    /// configuration file declaring it. Without `include`, every file not matched by
+    /// `ignore` is selected.
+    pub fn matches(&self, path: &Path, options: GlobOptions) -> bool {
+        let included = self
+            .include
+            .as_ref()
+            .is_none_or(|patterns| any_match(patterns, path, options));
+        let ignored = self
+            .ignore
+            .as_ref()
+            .is_some_and(|patterns| any_match(patterns, path, options));
+        included && !ignored
+    }
+}
+
+/// Patterns are validated when the configuration is parsed, invalid ones never match
+fn any_match(patterns: &[String], path: &Path, options: GlobOptions) -> bool {
+    patterns
+        .iter()
+        .filter_map(|pattern| Glob::parse(pattern, options).ok())
+        .any(|glob| glob.is_match(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_configuration;
+
+    fn overrides() -> Vec<PartialOverrideConfiguration> {
+        parse_configuration(
+            r#"{
+  "overrides": [
+    { "include": ["slides/**"] },
+    { "include": ["i18n/fr/**"], "ignore": ["i18n/fr/legal/**"] }
+  ]
+}"#,
+        )
+        .unwrap()
+        .configuration
+        .overrides
+        .unwrap()
+    }
+
+    #[test]
+    fn test_override_include() {
+        let entry = &overrides()[0];
+        assert!(entry.matches(Path::new("slides/intro.md"), GlobOptions::default()));
+        assert!(entry.matches(Path::new("slides/2024/intro.md"), GlobOptions::default()));
+        assert!(!entry.matches(Path::new("docs/intro.md"), GlobOptions::default()));
+    }
+
+    #[test]
+    fn test_override_ignore() {
+        let entry = &overrides()[1];
+        assert!(entry.matches(Path::new("i18n/fr/guide.md"), GlobOptions::default()));
+        assert!(!entry.matches(Path::new("i18n/fr/legal/terms.md"), GlobOptions::default()));
+    }
+
+    #[test]
+    fn test_override_case_insensitive() {
+        let entry = &overrides()[0];
+        let options = GlobOptions {
+            case_sensitive: false,
+        };
+        assert!(!entry.matches(Path::new("Slides/intro.md"), GlobOptions::default()));
+        assert!(entry.matches(Path::new("Slides/intro.md"), options));
+    }
+
+    #[test]
+    fn test_override_without_include_matches_everything_not_ignored() {
+        let entry = PartialOverrideConfiguration {
+            ignore: Some(vec!["vendor/**".to_string()]),
+            ..Default::default()
+        };
+        assert!(entry.matches(Path::new("README.md"), GlobOptions::default()));
+        assert!(!entry.matches(Path::new("vendor/lib/README.md"), GlobOptions::default()));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{MarkdownFormatterConfig, RulesConfig};
+use serde_json::{Map, Value};
+
+/// A curated set of rule settings for the output of a particular assistant
+#[derive(Debug)]
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Options of each rule in the registry, as `(rule, &[(option, value)])`
+    pub rules: &'static [(&'static str, &'static [(&'static str, bool)])],
+}
+
+impl Preset {
+    /// Expands the preset into Markdown formatter configuration
+    pub fn configuration(&self) -> MarkdownFormatterConfig {
+        let rules: Map<String, Value> = self
+            .rules
+            .iter()
+            .map(|(rule, options)| {
+                let options: Map<String, Value> = options
+                    .iter()
+                    .map(|(option, value)| (option.to_string(), Value::Bool(*value)))
+                    .collect();
+                (rule.to_string(), Value::Object(options))
+            })
+            .collect();
+        let rules: RulesConfig = serde_json::from_value(Value::Object(rules))
+            .expect("presets only use options from the rule registry");
+        MarkdownFormatterConfig {
+            enabled: Some(true),
+            rules,
+            ..Default::default()
+        }
+    }
+}
+
+/// Names of the built-in presets, as accepted by `preset` and `--preset`
+pub const PRESET_NAMES: &[&str] = &["chatgpt", "gemini", "copilot", "deepseek"];
+
+/// Every built-in preset
+pub static PRESETS: &[Preset] = &[
+    Preset {
+        name: "chatgpt",
+        description: "Bold headings, smart quotes, em dashes, stray horizontal rules and emoji",
+        rules: &[
+            ("smart_quotes", &[("enabled", true)]),
+            ("headings", &[("enabled", true)]),
+            (
+                "remove_horizontal_rules",
+                &[("enabled", true), ("retain_frontmatter_wrappers", true)],
+            ),
+            (
+                "punctuation",
+                &[
+                    ("enabled", true),
+                    ("standardize_dashes", true),
+                    ("standardize_ellipsis", true),
+                ],
+            ),
+            (
+                "emoji",
+                &[
+                    ("enabled", true),
+                    ("strip_headings", true),
+                    ("strip_bullets", true),
+                ],
+            ),
+        ],
+    },
+    Preset {
+        name: "gemini",
+        description: "Emoji-laden headings and smart quotes, keeping dashes and section breaks",
+        rules: &[
+            ("smart_quotes", &[("enabled", true)]),
+            ("headings", &[("enabled", true)]),
+            ("remove_horizontal_rules", &[("enabled", false)]),
+            (
+                "punctuation",
+                &[
+                    ("enabled", true),
+                    ("standardize_dashes", false),
+                    ("standardize_ellipsis", true),
+                ],
+            ),
+            (
+                "emoji",
+                &[
+                    ("enabled", true),
+                    ("strip_headings", true),
+                    ("strip_bullets", true),
+                ],
+            ),
+        ],
+    },
+    Preset {
+        name: "copilot",
+        description: "Smart punctuation only, leaving document structure untouched",
+        rules: &[
+            ("smart_quotes", &[("enabled", true)]),
+            ("headings", &[("enabled", false)]),
+            ("remove_horizontal_rules", &[("enabled", false)]),
+            (
+                "punctuation",
+                &[
+                    ("enabled", true),
+                    ("standardize_dashes", true),
+                    ("standardize_ellipsis", true),
+                ],
+            ),
+        ],
+    },
+    Preset {
+        name: "deepseek",
+        description: "Reasoning blocks, bold headings, smart quotes and em dashes",
+        rules: &[
+            ("smart_quotes", &[("enabled", true)]),
+            ("headings", &[("enabled", true)]),
+            (
+                "remove_horizontal_rules",
+                &[("enabled", true), ("retain_frontmatter_wrappers", true)],
+            ),
+            (
+                "punctuation",
+                &[
+                    ("enabled", true),
+                    ("standardize_dashes", true),
+                    ("standardize_ellipsis", false),
+                ],
+            ),
+            ("reasoning_blocks", &[("enabled", true)]),
+        ],
+    },
+];
+
+/// Looks up a built-in preset by name
+pub fn find_preset(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_configuration, validate_value, Schema};
+
+    #[test]
+    fn test_presets_use_registered_rules() {
+        for preset in PRESETS {
+            let value = serde_json::to_value(preset.configuration().rules).unwrap();
+            assert_eq!(
+                validate_value(&value, &Schema::Rules),
+                vec![],
+                "{}",
+                preset.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_preset_names_match_presets() {
+        let names: Vec<_> = PRESETS.iter().map(|preset| preset.name).collect();
+        assert_eq!(names, PRESET_NAMES);
+    }
+
+    #[test]
+    fn test_presets_target_their_assistant() {
+        let gemini = find_preset("gemini").unwrap().configuration().rules;
+        assert_eq!(gemini.emoji.strip_headings, Some(true));
+        let deepseek = find_preset("deepseek").unwrap().configuration().rules;
+        assert_eq!(deepseek.reasoning_blocks.enabled, Some(true));
+    }
+
+    #[test]
+    fn test_unknown_preset_suggests_name() {
+        let diagnostics = parse_configuration(r#"{ "preset": "chatgtp" }"#).unwrap_err();
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown value `chatgtp` for `preset`, expected one of `chatgpt`, `gemini`, \
+             `copilot`, `deepseek`. Did you mean `chatgpt`?"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::schema::{Field, ValueKind};
+
+/// A formatting rule and the options it accepts under `markdown.rules`
+#[derive(Debug)]
+pub struct RuleMetadata {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub options: &'static [Field],
+    /// markdownlint rules configuring the same behaviour, used by `ripari migrate`
+    pub markdownlint: &'static [Equivalent],
+}
+
+/// A markdownlint rule whose enabled state maps onto a boolean option of a ripari rule
+#[derive(Debug)]
+pub struct Equivalent {
+    /// Every name of the markdownlint rule, e.g. `MD036` and `no-emphasis-as-heading`
+    pub names: &'static [&'static str],
+    pub option: &'static str,
+}
+
+impl RuleMetadata {
+    pub fn find_option(&self, name: &str) -> Option<&'static Field> {
+        self.options.iter().find(|option| option.name == name)
+    }
+}
+
+const ENABLED: Field = Field::option("enabled", ValueKind::Boolean, "Enables the rule");
+
+/// Every rule known to ripari
+pub static RULES: &[RuleMetadata] = &[
+    RuleMetadata {
+        name: "smart_quotes",
+        description: "Converts smart quotes to straight ASCII quotes",
+        options: &[
+            ENABLED,
+            Field::option(
+                "low_quotes",
+                ValueKind::Boolean,
+                "Also converts the low-9 quotes `„` and `‚`",
+            ),
+            Field::option(
+                "guillemets",
+                ValueKind::Boolean,
+                "Also converts the guillemets `«` `»` and `‹` `›`",
+            ),
+            Field::option(
+                "modifier_apostrophes",
+                ValueKind::Boolean,
+                "Also converts U+02BC modifier letter apostrophes",
+            ),
+            Field::option(
+                "full_width",
+                ValueKind::Boolean,
+                "Also converts the full-width quotes `＂` and `＇`",
+            ),
+            Field::option(
+                "primes",
+                ValueKind::Boolean,
+                "Converts the primes of measurements such as `5′10″` to `'` and `\"`",
+            ),
+        ],
+        markdownlint: &[],
+    },
+    RuleMetadata {
+        name: "headings",
+        description: "Removes emphasis wrapped around heading text",
+        options: &[
+            ENABLED,
+            Field::option(
+                "remove_emphasis",
+                ValueKind::Boolean,
+                "Removes `**bold**` wrapped around the whole heading",
+            )
+            .deprecated("It is all the rule does, use `headings.enabled` instead."),
+        ],
+        markdownlint: &[Equivalent {
+            names: &["MD036", "no-emphasis-as-heading"],
+            option: "enabled",
+        }],
+    },
+    RuleMetadata {
+        name: "remove_horizontal_rules",
+        description: "Removes `---` separators outside of code blocks",
+        options: &[
+            ENABLED,
+            Field::option(
+                "retain_frontmatter_wrappers",
+                ValueKind::Boolean,
+                "Keeps the `---` lines delimiting YAML front matter",
+            ),
+        ],
+        // `MD035` (`hr-style`) asks for a consistent style, not for no rules at all
+        markdownlint: &[],
+    },
+    RuleMetadata {
+        name: "punctuation",
+        description: "Standardises typographic punctuation",
+        options: &[
+            ENABLED,
+            Field::option(
+                "standardize_dashes",
+                ValueKind::BooleanOrEnum(&[
+                    "hyphen",
+                    "spaced_hyphen",
+                    "comma",
+                    "double_hyphen",
+                    "keep_en_dash_for_ranges",
+                ]),
+                "Replaces em and en dashes using a strategy, `true` standing for `spaced_hyphen`",
+            ),
+            Field::option(
+                "standardize_ellipsis",
+                ValueKind::Boolean,
+                "Converts `…` to three dots",
+            ),
+        ],
+        markdownlint: &[],
+    },
+    RuleMetadata {
+        name: "math",
+        description: "Keeps other rules out of LaTeX math and unifies its delimiters",
+        options: &[
+            ENABLED,
+            Field::option(
+                "delimiters",
+                ValueKind::Enum(&["dollars", "brackets"]),
+                "Rewrites math with `$`/`$$` or `\\(`/`\\[` delimiters",
+            ),
+        ],
+        markdownlint: &[],
+    },
+    RuleMetadata {
+        name: "emoji",
+        description: "Removes decorative emoji or converts them to shortcodes",
+        options: &[
+            ENABLED,
+            Field::option(
+                "strip_headings",
+                ValueKind::Boolean,
+                "Removes emoji from headings",
+            ),
+            Field::option(
+                "strip_bullets",
+                ValueKind::Boolean,
+                "Removes emoji starting a line or list item, used as bullets",
+            ),
+            Field::option(
+                "body_text",
+                ValueKind::Enum(&["keep", "remove", "shortcode"]),
+                "Keeps, removes or converts to `:shortcode:` the other emoji",
+            ),
+        ],
+        markdownlint: &[],
+    },
+    RuleMetadata {
+        name: "invisible_characters",
+        description: "Normalises non-standard spaces and hyphens and removes invisible characters",
+        options: &[
+            ENABLED,
+            Field::option(
+                "spaces",
+                ValueKind::Boolean,
+                "Replaces no-break, narrow and other Unicode spaces with a plain space",
+            ),
+            Field::option(
+                "hyphens",
+                ValueKind::Boolean,
+                "Replaces U+2010 hyphens and U+2011 non-breaking hyphens with `-`",
+            ),
+            Field::option(
+                "zero_width",
+                ValueKind::Boolean,
+                "Removes zero-width spaces, joiners and word joiners",
+            ),
+            Field::option(
+                "soft_hyphens",
+                ValueKind::Boolean,
+                "Removes U+00AD soft hyphens",
+            ),
+            Field::option(
+                "byte_order_marks",
+                ValueKind::Boolean,
+                "Removes U+FEFF byte order marks",
+            ),
+        ],
+        markdownlint: &[],
+    },
+    RuleMetadata {
+        name: "reasoning_blocks",
+        description: "Removes the `<think>` blocks reasoning models write before their answer",
+        options: &[ENABLED],
+        markdownlint: &[],
+    },
+];
+
+/// Looks up a rule by its configuration name
+pub fn find_rule(name: &str) -> Option<&'static RuleMetadata> {
+    RULES.iter().find(|rule| rule.name == name)
+}
+
+/// Finds the rule and option matching a markdownlint rule name, ignoring case
+pub fn find_markdownlint_equivalent(
+    name: &str,
+) -> Option<(&'static RuleMetadata, &'static Equivalent)> {
+    RULES.iter().find_map(|rule| {
+        rule.markdownlint
+            .iter()
+            .find(|equivalent| {
+                equivalent
+                    .names
+                    .iter()
+                    .any(|candidate| candidate.eq_ignore_ascii_case(name))
+            })
+            .map(|equivalent| (rule, equivalent))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+
+    #[test]
+    fn test_find_rule() {
+        let rule = find_rule("punctuation").unwrap();
+        assert_eq!(rule.name, "punctuation");
+        assert!(rule.find_option("standardize_dashes").is_some());
+        assert!(rule.find_option("standardize_quotes").is_none());
+        assert!(find_rule("smart_qoutes").is_none());
+    }
+
+    #[test]
+    fn test_markdownlint_equivalents_use_boolean_options() {
+        for rule in RULES {
+            for equivalent in rule.markdownlint {
+                let option = rule.find_option(equivalent.option).unwrap();
+                assert!(matches!(option.schema, Schema::Value(ValueKind::Boolean)));
+            }
+        }
+        let (rule, equivalent) = find_markdownlint_equivalent("md036").unwrap();
+        assert_eq!((rule.name, equivalent.option), ("headings", "enabled"));
+        assert!(find_markdownlint_equivalent("MD013").is_none());
+        assert!(find_markdownlint_equivalent("hr-style").is_none());
+    }
+
+    #[test]
+    fn test_every_rule_can_be_toggled() {
+        for rule in RULES {
+            assert!(
+                rule.find_option("enabled").is_some(),
+                "{} has no `enabled` option",
+                rule.name
+            );
+        }
+    }
+}
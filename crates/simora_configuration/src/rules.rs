@@ -1,35 +1,222 @@
+use crate::Merge;
 use serde::{Deserialize, Serialize};
 
+/// Overwrites each option of `target` that is set in `other`
+macro_rules! merge_options {
+    ($target:ident, $other:ident, $($field:ident),+) => {
+        $(
+            if $other.$field.is_some() {
+                $target.$field = $other.$field;
+            }
+        )+
+    };
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SmartQuotesConfig {
-    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_quotes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guillemets: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifier_apostrophes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_width: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primes: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HeadingsConfig {
-    pub enabled: bool,
-    pub remove_emphasis: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_emphasis: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HorizontalRulesConfig {
-    #[serde(default)]
-    pub enabled: bool,
-    #[serde(default)]
-    pub retain_frontmatter_wrappers: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retain_frontmatter_wrappers: Option<bool>,
+}
+
+/// An option set to `true`, `false` or the name of a strategy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StrategyOption {
+    Enabled(bool),
+    Named(String),
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PunctuationConfig {
-    pub enabled: bool,
-    pub standardize_dashes: bool,
-    pub standardize_ellipsis: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// `true` for the `spaced_hyphen` strategy, `false` to keep dashes, or a strategy name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standardize_dashes: Option<StrategyOption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standardize_ellipsis: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MathConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Delimiters math is rewritten with: `dollars` or `brackets`. Unset keeps them as written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delimiters: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmojiConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strip_headings: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strip_bullets: Option<bool>,
+    /// What happens to the remaining emoji: `keep`, `remove` or `shortcode`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvisibleCharactersConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spaces: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hyphens: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zero_width: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft_hyphens: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_order_marks: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReasoningBlocksConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RulesConfig {
+    #[serde(default)]
     pub smart_quotes: SmartQuotesConfig,
+    #[serde(default)]
     pub headings: HeadingsConfig,
+    #[serde(default)]
     pub remove_horizontal_rules: HorizontalRulesConfig,
+    #[serde(default)]
     pub punctuation: PunctuationConfig,
+    #[serde(default)]
+    pub math: MathConfig,
+    #[serde(default)]
+    pub emoji: EmojiConfig,
+    #[serde(default)]
+    pub invisible_characters: InvisibleCharactersConfig,
+    #[serde(default)]
+    pub reasoning_blocks: ReasoningBlocksConfig,
+}
+
+impl Merge for SmartQuotesConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(
+            self,
+            other,
+            enabled,
+            low_quotes,
+            guillemets,
+            modifier_apostrophes,
+            full_width,
+            primes
+        );
+    }
+}
+
+impl Merge for HeadingsConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(self, other, enabled, remove_emphasis);
+    }
+}
+
+impl Merge for HorizontalRulesConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(self, other, enabled, retain_frontmatter_wrappers);
+    }
+}
+
+impl Merge for PunctuationConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(
+            self,
+            other,
+            enabled,
+            standardize_dashes,
+            standardize_ellipsis
+        );
+    }
+}
+
+impl Merge for MathConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(self, other, enabled, delimiters);
+    }
+}
+
+impl Merge for EmojiConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(
+            self,
+            other,
+            enabled,
+            strip_headings,
+            strip_bullets,
+            body_text
+        );
+    }
+}
+
+impl Merge for InvisibleCharactersConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(
+            self,
+            other,
+            enabled,
+            spaces,
+            hyphens,
+            zero_width,
+            soft_hyphens,
+            byte_order_marks
+        );
+    }
+}
+
+impl Merge for ReasoningBlocksConfig {
+    fn merge_with(&mut self, other: Self) {
+        merge_options!(self, other, enabled);
+    }
+}
+
+impl Merge for RulesConfig {
+    fn merge_with(&mut self, other: Self) {
+        self.smart_quotes.merge_with(other.smart_quotes);
+        self.headings.merge_with(other.headings);
+        self.remove_horizontal_rules
+            .merge_with(other.remove_horizontal_rules);
+        self.punctuation.merge_with(other.punctuation);
+        self.math.merge_with(other.math);
+        self.emoji.merge_with(other.emoji);
+        self.invisible_characters
+            .merge_with(other.invisible_characters);
+        self.reasoning_blocks.merge_with(other.reasoning_blocks);
+    }
 }
@@ -0,0 +1,195 @@
+use crate::presets::PRESET_NAMES;
+use std::fmt;
+
+/// The type of value accepted by a configuration option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Boolean,
+    /// A non-negative integer
+    Integer,
+    String,
+    StringArray,
+    /// An array of patterns that must compile with `simora_glob`
+    GlobArray,
+    Enum(&'static [&'static str]),
+    /// `true`, `false` or one of the given strategy names
+    BooleanOrEnum(&'static [&'static str]),
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueKind::Boolean => write!(f, "a boolean"),
+            ValueKind::Integer => write!(f, "a non-negative integer"),
+            ValueKind::String => write!(f, "a string"),
+            ValueKind::StringArray => write!(f, "an array of strings"),
+            ValueKind::GlobArray => write!(f, "an array of glob patterns"),
+            ValueKind::Enum(variants) => {
+                let variants: Vec<String> = variants.iter().map(|v| format!("`{}`", v)).collect();
+                write!(f, "one of {}", variants.join(", "))
+            }
+            ValueKind::BooleanOrEnum(variants) => {
+                write!(f, "a boolean or {}", ValueKind::Enum(variants))
+            }
+        }
+    }
+}
+
+/// The shape of a configuration value
+#[derive(Debug)]
+pub enum Schema {
+    Value(ValueKind),
+    Object(&'static [Field]),
+    /// An array whose items are objects with the given fields
+    ObjectArray(&'static [Field]),
+    /// The `markdown.rules` object, whose members are described by the rule registry
+    Rules,
+}
+
+/// A key accepted inside a configuration object
+#[derive(Debug)]
+pub struct Field {
+    pub name: &'static str,
+    pub schema: Schema,
+    pub description: &'static str,
+    /// Migration hint shown when the key is still in use
+    pub deprecated: Option<&'static str>,
+}
+
+impl Field {
+    pub const fn new(name: &'static str, schema: Schema, description: &'static str) -> Self {
+        Self {
+            name,
+            schema,
+            description,
+            deprecated: None,
+        }
+    }
+
+    pub const fn option(name: &'static str, kind: ValueKind, description: &'static str) -> Self {
+        Self::new(name, Schema::Value(kind), description)
+    }
+
+    pub const fn deprecated(mut self, hint: &'static str) -> Self {
+        self.deprecated = Some(hint);
+        self
+    }
+}
+
+/// The members of `markdown`, also accepted by each entry of `overrides`
+const MARKDOWN_FIELDS: &[Field] = &[
+    Field::option(
+        "enabled",
+        ValueKind::Boolean,
+        "Enables the Markdown formatter",
+    ),
+    Field::new("rules", Schema::Rules, "Per-rule configuration"),
+    Field::option(
+        "end_of_line",
+        ValueKind::Enum(&["lf", "crlf", "cr"]),
+        "Line ending written after every line",
+    ),
+    Field::option(
+        "insert_final_newline",
+        ValueKind::Boolean,
+        "Ends files with a line ending, or removes it when false",
+    ),
+    Field::option(
+        "trim_trailing_whitespace",
+        ValueKind::Boolean,
+        "Removes whitespace at the end of lines, keeping hard line breaks",
+    ),
+    Field::option(
+        "indent_style",
+        ValueKind::Enum(&["space", "tab"]),
+        "Expands leading tabs with `space`, or turns four leading spaces into a tab with `tab`",
+    ),
+    Field::option(
+        "max_line_length",
+        ValueKind::Integer,
+        "Reports lines longer than this",
+    ),
+];
+
+/// The schema of `ripari.json`
+pub static CONFIGURATION_SCHEMA: Schema = Schema::Object(&[
+    Field::option(
+        "extends",
+        ValueKind::StringArray,
+        "Configuration files or packages merged underneath this one",
+    ),
+    Field::option(
+        "preset",
+        ValueKind::Enum(PRESET_NAMES),
+        "Built-in rule settings for the output of a particular assistant",
+    ),
+    Field::new(
+        "markdown",
+        Schema::Object(MARKDOWN_FIELDS),
+        "Markdown formatter configuration",
+    ),
+    Field::new(
+        "files",
+        Schema::Object(&[
+            Field::option(
+                "ignore",
+                ValueKind::GlobArray,
+                "Glob patterns of files to skip",
+            ),
+            Field::option(
+                "include",
+                ValueKind::GlobArray,
+                "Glob patterns of files to process",
+            ),
+            Field::option(
+                "case_sensitive",
+                ValueKind::Boolean,
+                "Whether patterns in `files` and `overrides` match letters in case",
+            ),
+        ]),
+        "File selection",
+    ),
+    Field::new(
+        "vcs",
+        Schema::Object(&[
+            Field::option("enabled", ValueKind::Boolean, "Enables VCS integration"),
+            Field::option(
+                "client_kind",
+                ValueKind::Enum(&["git"]),
+                "The VCS client in use",
+            ),
+            Field::option(
+                "use_ignore_file",
+                ValueKind::Boolean,
+                "Skips files ignored by the VCS",
+            ),
+        ]),
+        "Version control integration",
+    ),
+    Field::new(
+        "overrides",
+        Schema::ObjectArray(&[
+            Field::option(
+                "include",
+                ValueKind::GlobArray,
+                "Glob patterns of files the override applies to",
+            ),
+            Field::option(
+                "ignore",
+                ValueKind::GlobArray,
+                "Glob patterns of files the override does not apply to",
+            ),
+            Field::new(
+                "markdown",
+                Schema::Object(MARKDOWN_FIELDS),
+                "Markdown formatter configuration for matching files",
+            ),
+        ]),
+        "Configuration applied to files matching glob patterns, in order",
+    ),
+    Field::option(
+        "root",
+        ValueKind::Boolean,
+        "Stops the search for configuration files in parent directories",
+    ),
+]);
@@ -0,0 +1,42 @@
+/// Returns the candidate closest to `input`, if it is close enough to be a likely typo
+pub fn did_you_mean<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let threshold = (input.chars().count() / 3).max(2);
+    candidates
+        .map(|candidate| (levenshtein(input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = ["headings", "punctuation"];
+        assert_eq!(
+            did_you_mean("heading", candidates.into_iter()),
+            Some("headings")
+        );
+        assert_eq!(
+            did_you_mean("punctation", candidates.into_iter()),
+            Some("punctuation")
+        );
+        assert_eq!(did_you_mean("emoji", candidates.into_iter()), None);
+    }
+}
@@ -1,16 +1,35 @@
-use serde::{Deserialize, Serialize};
 use super::rules::RulesConfig;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MarkdownFormatterConfig {
-    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default)]
     pub rules: RulesConfig,
+    /// Line ending written after every line: `lf`, `crlf` or `cr`. Unset keeps the original.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_of_line: Option<String>,
+    /// Ends files with a line ending when true, removes it when false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insert_final_newline: Option<bool>,
+    /// Removes whitespace at the end of lines, keeping Markdown hard line breaks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_trailing_whitespace: Option<bool>,
+    /// `space` expands leading tabs, `tab` turns each four columns of leading spaces into a tab
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indent_style: Option<String>,
+    /// Lines longer than this are reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_line_length: Option<u32>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PartialFilesConfiguration {
     pub ignore: Option<Vec<String>>,
     pub include: Option<Vec<String>>,
+    /// Whether patterns in `files` and `overrides` match letters in case, `true` by default
+    pub case_sensitive: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -20,11 +39,31 @@ pub struct PartialVcsConfiguration {
     pub use_ignore_file: Option<bool>,
 }
 
+/// Configuration applied only to files matching `include` and not matching `ignore`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PartialOverrideConfiguration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markdown: Option<MarkdownFormatterConfig>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PartialMarkdownFormatterConfiguration {
+    /// Configuration files merged underneath this one, in order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<Vec<String>>,
+    /// Built-in preset expanded underneath this file's settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
     pub markdown: Option<MarkdownFormatterConfig>,
     pub files: Option<PartialFilesConfiguration>,
     pub vcs: Option<PartialVcsConfiguration>,
+    /// Applied in order on top of the rest of the configuration for matching files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<Vec<PartialOverrideConfiguration>>,
     #[serde(default)]
     pub root: bool,
 }
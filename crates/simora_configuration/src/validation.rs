@@ -0,0 +1,533 @@
+use crate::formats::ConfigurationFormat;
+use crate::location::Location;
+use crate::registry::{find_rule, RULES};
+use crate::schema::{Field, Schema, ValueKind, CONFIGURATION_SCHEMA};
+use crate::suggestion::did_you_mean;
+use crate::PartialMarkdownFormatterConfiguration;
+use serde_json::Value;
+use simora_glob::Glob;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found while loading a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationDiagnostic {
+    pub severity: Severity,
+    /// JSON path of the offending value, e.g. `markdown.rules.smart_quotes`
+    pub path: String,
+    pub message: String,
+    pub location: Option<Location>,
+    /// Lines shown below the message, such as the offending value and a suggested fix
+    pub notes: Vec<String>,
+}
+
+impl ConfigurationDiagnostic {
+    pub(crate) fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+            location: None,
+            notes: Vec::new(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(path, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for ConfigurationDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// A successfully parsed configuration together with any non-fatal diagnostics
+#[derive(Debug)]
+pub struct ParsedConfiguration {
+    pub configuration: PartialMarkdownFormatterConfiguration,
+    pub warnings: Vec<ConfigurationDiagnostic>,
+}
+
+/// Parses and strictly validates the content of a `ripari.json` file
+pub fn parse_configuration(
+    source: &str,
+) -> Result<ParsedConfiguration, Vec<ConfigurationDiagnostic>> {
+    parse_configuration_as(source, ConfigurationFormat::Json)
+}
+
+/// Parses and strictly validates the content of a configuration file written in `format`
+pub fn parse_configuration_as(
+    source: &str,
+    format: ConfigurationFormat,
+) -> Result<ParsedConfiguration, Vec<ConfigurationDiagnostic>> {
+    let value = format.parse(source).map_err(|error| {
+        vec![ConfigurationDiagnostic {
+            location: error.location,
+            ..ConfigurationDiagnostic::error("", error.message)
+        }]
+    })?;
+
+    let mut diagnostics = validate_value(&value, &CONFIGURATION_SCHEMA);
+    for diagnostic in &mut diagnostics {
+        diagnostic.location = format.find_path(source, &diagnostic.path);
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.location);
+    if diagnostics.iter().any(ConfigurationDiagnostic::is_error) {
+        return Err(diagnostics);
+    }
+
+    let configuration = serde_json::from_value(value)
+        .map_err(|e| vec![ConfigurationDiagnostic::error("", e.to_string())])?;
+
+    Ok(ParsedConfiguration {
+        configuration,
+        warnings: diagnostics,
+    })
+}
+
+/// Checks `value` against `schema`, reporting unknown keys, type mismatches and deprecated keys
+pub fn validate_value(value: &Value, schema: &Schema) -> Vec<ConfigurationDiagnostic> {
+    let mut diagnostics = Vec::new();
+    validate_node(value, schema, "", &mut diagnostics);
+    diagnostics
+}
+
+fn validate_node(
+    value: &Value,
+    schema: &Schema,
+    path: &str,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) {
+    match schema {
+        Schema::Value(kind) => validate_kind(value, *kind, path, diagnostics),
+        Schema::Object(fields) => {
+            let Some(members) = expect_object(value, path, diagnostics) else {
+                return;
+            };
+            for (key, member) in members {
+                let member_path = join(path, key);
+                match fields.iter().find(|field| field.name == key) {
+                    Some(field) => validate_field(member, field, &member_path, diagnostics),
+                    None => diagnostics.push(unknown_key(
+                        key,
+                        path,
+                        fields.iter().map(|field| field.name),
+                    )),
+                }
+            }
+        }
+        Schema::ObjectArray(fields) => {
+            let Some(items) = value.as_array() else {
+                diagnostics.push(ConfigurationDiagnostic::error(
+                    path,
+                    format!(
+                        "Expected an array of objects for `{}`, found {}.",
+                        path,
+                        describe(value)
+                    ),
+                ));
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                let item_path = format!("{}[{}]", path, index);
+                validate_node(item, &Schema::Object(fields), &item_path, diagnostics);
+            }
+        }
+        Schema::Rules => {
+            let Some(members) = expect_object(value, path, diagnostics) else {
+                return;
+            };
+            for (key, member) in members {
+                let member_path = join(path, key);
+                match find_rule(key) {
+                    Some(rule) => validate_node(
+                        member,
+                        &Schema::Object(rule.options),
+                        &member_path,
+                        diagnostics,
+                    ),
+                    None => {
+                        diagnostics.push(unknown_key(key, path, RULES.iter().map(|rule| rule.name)))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn validate_field(
+    value: &Value,
+    field: &Field,
+    path: &str,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) {
+    if let Some(hint) = field.deprecated {
+        diagnostics.push(ConfigurationDiagnostic::warning(
+            path,
+            format!("`{}` is deprecated. {}", path, hint),
+        ));
+    }
+    validate_node(value, &field.schema, path, diagnostics);
+}
+
+fn validate_kind(
+    value: &Value,
+    kind: ValueKind,
+    path: &str,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) {
+    let valid = match (kind, value) {
+        (ValueKind::Boolean, Value::Bool(_)) | (ValueKind::String, Value::String(_)) => true,
+        (ValueKind::Integer, Value::Number(number)) => number.as_u64().is_some(),
+        (ValueKind::StringArray, Value::Array(items)) => items.iter().all(Value::is_string),
+        (ValueKind::GlobArray, Value::Array(items)) if items.iter().all(Value::is_string) => {
+            for (index, pattern) in items.iter().filter_map(Value::as_str).enumerate() {
+                if let Err(error) = pattern.parse::<Glob>() {
+                    let item = format!("{}[{}]", path, index);
+                    let message = format!("Invalid glob `{}` in `{}`: {}", pattern, item, error);
+                    let mut notes: Vec<String> = error
+                        .highlight(pattern)
+                        .map(|highlight| highlight.lines().map(str::to_string).collect())
+                        .unwrap_or_default();
+                    notes.extend(
+                        error
+                            .suggestion(pattern)
+                            .map(|hint| format!("help: {}", hint)),
+                    );
+                    diagnostics.push(ConfigurationDiagnostic {
+                        notes,
+                        ..ConfigurationDiagnostic::error(item, message)
+                    });
+                }
+            }
+            true
+        }
+        (ValueKind::Enum(variants), Value::String(variant)) => {
+            if !variants.contains(&variant.as_str()) {
+                let mut message = format!(
+                    "Unknown value `{}` for `{}`, expected {}.",
+                    variant, path, kind
+                );
+                if let Some(suggestion) = did_you_mean(variant, variants.iter().copied()) {
+                    message.push_str(&format!(" Did you mean `{}`?", suggestion));
+                }
+                diagnostics.push(ConfigurationDiagnostic::error(path, message));
+            }
+            true
+        }
+        (ValueKind::BooleanOrEnum(_), Value::Bool(_)) => true,
+        (ValueKind::BooleanOrEnum(variants), Value::String(_)) => {
+            validate_kind(value, ValueKind::Enum(variants), path, diagnostics);
+            true
+        }
+        _ => false,
+    };
+    if !valid {
+        diagnostics.push(ConfigurationDiagnostic::error(
+            path,
+            format!(
+                "Expected {} for `{}`, found {}.",
+                kind,
+                path,
+                describe(value)
+            ),
+        ));
+    }
+}
+
+fn expect_object<'a>(
+    value: &'a Value,
+    path: &str,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> Option<&'a serde_json::Map<String, Value>> {
+    let members = value.as_object();
+    if members.is_none() {
+        let target = if path.is_empty() {
+            "the configuration".to_string()
+        } else {
+            format!("`{}`", path)
+        };
+        diagnostics.push(ConfigurationDiagnostic::error(
+            path,
+            format!(
+                "Expected an object for {}, found {}.",
+                target,
+                describe(value)
+            ),
+        ));
+    }
+    members
+}
+
+fn unknown_key<'a>(
+    key: &str,
+    parent: &str,
+    known: impl Iterator<Item = &'a str> + Clone,
+) -> ConfigurationDiagnostic {
+    let mut message = if parent.is_empty() {
+        format!("Unknown key `{}`.", key)
+    } else {
+        format!("Unknown key `{}` in `{}`.", key, parent)
+    };
+    match did_you_mean(key, known.clone()) {
+        Some(suggestion) => message.push_str(&format!(" Did you mean `{}`?", suggestion)),
+        None => {
+            let known: Vec<String> = known.map(|name| format!("`{}`", name)).collect();
+            message.push_str(&format!(" Known keys are {}.", known.join(", ")));
+        }
+    }
+    ConfigurationDiagnostic::error(join(parent, key), message)
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_configuration() {
+        let parsed = parse_configuration(
+            r#"{
+  "markdown": {
+    "enabled": true,
+    "rules": {
+      "smart_quotes": { "enabled": true },
+      "headings": { "enabled": true },
+      "remove_horizontal_rules": { "enabled": true, "retain_frontmatter_wrappers": true },
+      "punctuation": { "enabled": true, "standardize_dashes": true, "standardize_ellipsis": true }
+    }
+  },
+  "files": { "include": ["**/*.md"] },
+  "root": true
+}"#,
+        )
+        .unwrap();
+        assert!(parsed.warnings.is_empty());
+        assert!(parsed.configuration.root);
+    }
+
+    #[test]
+    fn test_unknown_rule_suggests_closest_name() {
+        let diagnostics = parse_configuration(
+            r#"{
+  "markdown": {
+    "rules": {
+      "smart_qoutes": { "enabled": false }
+    }
+  }
+}"#,
+        )
+        .unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "markdown.rules.smart_qoutes");
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown key `smart_qoutes` in `markdown.rules`. Did you mean `smart_quotes`?"
+        );
+        assert_eq!(
+            diagnostics[0].location,
+            Some(Location { line: 4, column: 7 })
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_without_close_match_lists_known_keys() {
+        let diagnostics = validate_value(&json!({ "formatter": {} }), &CONFIGURATION_SCHEMA);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown key `formatter`. Known keys are `extends`, `preset`, `markdown`, `files`, `vcs`, `overrides`, `root`."
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        let diagnostics = validate_value(
+            &json!({ "markdown": { "rules": { "headings": { "enabled": "yes" } } } }),
+            &CONFIGURATION_SCHEMA,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Expected a boolean for `markdown.rules.headings.enabled`, found a string."
+        );
+    }
+
+    #[test]
+    fn test_string_array_mismatch() {
+        let diagnostics = validate_value(
+            &json!({ "extends": ["./a.json", 1] }),
+            &CONFIGURATION_SCHEMA,
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "Expected an array of strings for `extends`, found an array."
+        );
+    }
+
+    #[test]
+    fn test_overrides() {
+        let parsed = parse_configuration(
+            r#"{
+  "overrides": [
+    {
+      "include": ["slides/**"],
+      "markdown": { "rules": { "remove_horizontal_rules": { "enabled": false } } }
+    }
+  ]
+}"#,
+        )
+        .unwrap();
+        let overrides = parsed.configuration.overrides.unwrap();
+        assert_eq!(overrides[0].include, Some(vec!["slides/**".to_string()]));
+
+        let diagnostics = validate_value(
+            &json!({ "overrides": [{ "include": ["a/**"] }, { "markdwn": {} }] }),
+            &CONFIGURATION_SCHEMA,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "overrides[1].markdwn");
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown key `markdwn` in `overrides[1]`. Did you mean `markdown`?"
+        );
+    }
+
+    #[test]
+    fn test_overrides_invalid_glob() {
+        let diagnostics = validate_value(
+            &json!({ "overrides": [{ "include": ["docs/**", "a/**b"] }] }),
+            &CONFIGURATION_SCHEMA,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "overrides[0].include[1]");
+        assert_eq!(
+            diagnostics[0].message,
+            "Invalid glob `a/**b` in `overrides[0].include[1]`: `**` must be enclosed by the path separator `/`, or the start/end of the glob and mustn't be followed by `/**`."
+        );
+        assert_eq!(
+            diagnostics[0].notes,
+            [
+                "a/**b",
+                "  ^",
+                "help: Use `a/*b` to match within a single directory."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_suggestion() {
+        let diagnostics = validate_value(
+            &json!({ "vcs": { "client_kind": "gti" } }),
+            &CONFIGURATION_SCHEMA,
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown value `gti` for `vcs.client_kind`, expected one of `git`. Did you mean `git`?"
+        );
+    }
+
+    #[test]
+    fn test_boolean_or_strategy() {
+        let rules = |dashes: Value| {
+            let punctuation = json!({ "standardize_dashes": dashes });
+            json!({ "markdown": { "rules": { "punctuation": punctuation } } })
+        };
+        assert!(validate_value(&rules(json!(false)), &CONFIGURATION_SCHEMA).is_empty());
+        assert!(validate_value(&rules(json!("comma")), &CONFIGURATION_SCHEMA).is_empty());
+        let diagnostics = validate_value(&rules(json!("coma")), &CONFIGURATION_SCHEMA);
+        assert!(diagnostics[0].message.ends_with("Did you mean `comma`?"));
+        let diagnostics = validate_value(&rules(json!(1)), &CONFIGURATION_SCHEMA);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("Expected a boolean or one of `hyphen`, `spaced_hyphen`"));
+    }
+
+    #[test]
+    fn test_root_must_be_object() {
+        let diagnostics = parse_configuration("[]").unwrap_err();
+        assert_eq!(
+            diagnostics[0].message,
+            "Expected an object for the configuration, found an array."
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_has_location() {
+        let diagnostics = parse_configuration("{\n  \"root\": true,\n}").unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].location,
+            Some(Location { line: 3, column: 1 })
+        );
+        assert!(!diagnostics[0].message.contains("at line"));
+    }
+
+    #[test]
+    fn test_deprecated_key_is_a_warning() {
+        static SCHEMA: Schema = Schema::Object(&[
+            Field::option("legacy", ValueKind::Boolean, "").deprecated("Use `modern` instead."),
+            Field::option("modern", ValueKind::Boolean, ""),
+        ]);
+        let diagnostics = validate_value(&json!({ "legacy": true }), &SCHEMA);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "`legacy` is deprecated. Use `modern` instead."
+        );
+
+        let parsed = parse_configuration(
+            r#"{ "markdown": { "rules": { "headings": { "remove_emphasis": false } } } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.warnings[0].message,
+            "`markdown.rules.headings.remove_emphasis` is deprecated. \
+             It is all the rule does, use `headings.enabled` instead."
+        );
+        let rules = parsed.configuration.markdown.unwrap().rules;
+        assert_eq!(rules.headings.remove_emphasis, Some(false));
+    }
+}
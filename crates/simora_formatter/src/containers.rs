@@ -0,0 +1,65 @@
+/// Splits `line` into the markers of the blockquotes and list items containing it and
+/// their content. Markers nest to any depth, e.g. `> - > ` in `> - > [!NOTE]`.
+pub(crate) fn split_container_markers(line: &str) -> (&str, &str) {
+    let bytes = line.as_bytes();
+    let mut end = 0;
+    loop {
+        let mut i = end;
+        while matches!(bytes.get(i), Some(b' ' | b'\t')) {
+            i += 1;
+        }
+        let marker_end = match bytes.get(i) {
+            Some(b'>') if bytes.get(i + 1) == Some(&b' ') => Some(i + 2),
+            Some(b'>') => Some(i + 1),
+            Some(b'-' | b'*' | b'+') => list_marker_end(bytes, i + 1),
+            Some(b'0'..=b'9') => {
+                let digits = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+                match bytes.get(i + digits) {
+                    Some(b'.' | b')') if digits <= 9 => list_marker_end(bytes, i + digits + 1),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match marker_end {
+            Some(marker_end) => end = marker_end,
+            None => return line.split_at(end),
+        }
+    }
+}
+
+/// The end of the spacing after a list item marker ending at `start`, which must be
+/// followed by a space, a tab or the end of the line
+fn list_marker_end(bytes: &[u8], start: usize) -> Option<usize> {
+    match bytes.get(start) {
+        None => Some(start),
+        Some(b' ' | b'\t') => {
+            let spacing = bytes[start..]
+                .iter()
+                .take_while(|b| matches!(b, b' ' | b'\t'))
+                .count();
+            Some(start + spacing)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_container_markers() {
+        assert_eq!(split_container_markers("> quote"), ("> ", "quote"));
+        assert_eq!(split_container_markers(">> deep"), (">> ", "deep"));
+        assert_eq!(
+            split_container_markers("> - > [!NOTE]"),
+            ("> - > ", "[!NOTE]")
+        );
+        assert_eq!(split_container_markers("  12. item"), ("  12. ", "item"));
+        assert_eq!(split_container_markers("* ## **H**"), ("* ", "## **H**"));
+        assert_eq!(split_container_markers("**bold**"), ("", "**bold**"));
+        assert_eq!(split_container_markers("---"), ("", "---"));
+        assert_eq!(split_container_markers("2024 was"), ("", "2024 was"));
+    }
+}
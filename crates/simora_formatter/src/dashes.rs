@@ -0,0 +1,199 @@
+use simora_configuration::StrategyOption;
+
+/// How `standardize_dashes` replaces em and en dashes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DashStrategy {
+    /// `word—word` becomes `word-word`
+    Hyphen,
+    /// `word—word` becomes `word - word`
+    SpacedHyphen,
+    /// `word—word` becomes `word, word`
+    Comma,
+    /// `word—word` becomes `word--word`
+    DoubleHyphen,
+    /// `1990–2000` becomes `1990-2000`, other dashes ` - `
+    KeepEnDashForRanges,
+}
+
+impl DashStrategy {
+    /// The strategy selected by `option`, `true` standing for `spaced_hyphen`, which never
+    /// joins the words around a dash
+    pub fn from_option(option: &StrategyOption) -> Option<Self> {
+        match option {
+            StrategyOption::Enabled(true) => Some(DashStrategy::SpacedHyphen),
+            StrategyOption::Enabled(false) => None,
+            StrategyOption::Named(name) => match name.as_str() {
+                "hyphen" => Some(DashStrategy::Hyphen),
+                "spaced_hyphen" => Some(DashStrategy::SpacedHyphen),
+                "comma" => Some(DashStrategy::Comma),
+                "double_hyphen" => Some(DashStrategy::DoubleHyphen),
+                "keep_en_dash_for_ranges" => Some(DashStrategy::KeepEnDashForRanges),
+                _ => None,
+            },
+        }
+    }
+
+    /// The replacement of the dash `c` between `before` and `after`, and whether it takes
+    /// the place of the spacing around it
+    fn replacement(
+        self,
+        c: char,
+        before: Option<char>,
+        after: Option<char>,
+    ) -> (&'static str, bool) {
+        match self {
+            DashStrategy::Hyphen => ("-", false),
+            DashStrategy::DoubleHyphen => ("--", false),
+            DashStrategy::SpacedHyphen => (" - ", true),
+            DashStrategy::Comma => (", ", true),
+            DashStrategy::KeepEnDashForRanges => {
+                let is_digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+                if c == '–' && is_digit(before) && is_digit(after) {
+                    ("-", false)
+                } else {
+                    (" - ", true)
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the em and en dashes of `text` following `strategy`. Strategies adding spaces
+/// replace the spacing around the dash, so words are never joined nor spaces doubled.
+/// `text` starts and ends its line as told, or else meets inline syntax such as a code
+/// span, next to which the spacing of a replacement is kept.
+pub(crate) fn replace_dashes(
+    text: &str,
+    strategy: DashStrategy,
+    starts_line: bool,
+    ends_line: bool,
+) -> String {
+    let is_dash = |c: &char| matches!(c, '—' | '–');
+    let is_space = |c: &char| matches!(c, ' ' | '\t');
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !is_dash(&c) {
+            result.push(c);
+            continue;
+        }
+        let (replacement, spaced) =
+            strategy.replacement(c, result.chars().last(), chars.peek().copied());
+        // A hyphen starting a line would make it a list item or a heading underline
+        let at_start = starts_line && result.trim_start_matches([' ', '\t']).is_empty();
+        if !spaced {
+            if at_start {
+                result.push('\\');
+            }
+            result.push_str(replacement);
+            continue;
+        }
+        // A run of dashes is a single break, replaced once
+        result.truncate(result.trim_end_matches([' ', '\t']).len());
+        let mut spaced_after = false;
+        while let Some(skipped) = chars.next_if(|c| is_dash(c) || is_space(c)) {
+            spaced_after = is_space(&skipped);
+        }
+        let at_end = ends_line && chars.peek().is_none();
+        let mut replacement = replacement;
+        // A comma neither starts nor ends a line, the dash is dropped there
+        if strategy == DashStrategy::Comma && (at_start || at_end) {
+            replacement = "";
+        }
+        if at_start {
+            replacement = replacement.trim_start();
+        }
+        if at_end && !spaced_after {
+            replacement = replacement.trim_end();
+        }
+        if at_start && !replacement.is_empty() {
+            result.push('\\');
+        }
+        result.push_str(replacement);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replaces the dashes of `text` taken as a whole line
+    fn replace(text: &str, strategy: DashStrategy) -> String {
+        replace_dashes(text, strategy, true, true)
+    }
+
+    #[test]
+    fn test_spacing_strategies() {
+        assert_eq!(replace("word—word", DashStrategy::Hyphen), "word-word");
+        assert_eq!(replace("a — b", DashStrategy::DoubleHyphen), "a -- b");
+        assert_eq!(
+            replace("word—word", DashStrategy::SpacedHyphen),
+            "word - word"
+        );
+        assert_eq!(
+            replace("a  —  b——c", DashStrategy::SpacedHyphen),
+            "a - b - c"
+        );
+        assert_eq!(replace("no — really", DashStrategy::Comma), "no, really");
+        assert_eq!(replace("wait—", DashStrategy::SpacedHyphen), "wait -");
+        assert_eq!(replace("wait — ", DashStrategy::SpacedHyphen), "wait - ");
+    }
+
+    #[test]
+    fn test_line_edges() {
+        assert_eq!(replace("— Ada", DashStrategy::SpacedHyphen), "\\- Ada");
+        assert_eq!(replace("—", DashStrategy::SpacedHyphen), "\\-");
+        assert_eq!(replace("— Ada", DashStrategy::Comma), "Ada");
+        assert_eq!(replace("wait—", DashStrategy::Comma), "wait");
+        assert_eq!(replace("wait — ", DashStrategy::Comma), "wait");
+        assert_eq!(replace("—Ada—", DashStrategy::Hyphen), "\\-Ada-");
+    }
+
+    #[test]
+    fn test_next_to_inline_syntax() {
+        // `see—` before a code span, `—then` after one
+        let before = |strategy| replace_dashes("see—", strategy, true, false);
+        assert_eq!(before(DashStrategy::SpacedHyphen), "see - ");
+        assert_eq!(before(DashStrategy::Comma), "see, ");
+        let after = |strategy| replace_dashes("—then", strategy, false, true);
+        assert_eq!(after(DashStrategy::SpacedHyphen), " - then");
+        assert_eq!(after(DashStrategy::Comma), ", then");
+        assert_eq!(
+            replace_dashes(" — then", DashStrategy::SpacedHyphen, false, true),
+            " - then"
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            replace(
+                "1990–2000 was—mostly—fine",
+                DashStrategy::KeepEnDashForRanges
+            ),
+            "1990-2000 was - mostly - fine"
+        );
+        assert_eq!(
+            replace("pages 3 – 7 and A–B", DashStrategy::KeepEnDashForRanges),
+            "pages 3 - 7 and A - B"
+        );
+    }
+
+    #[test]
+    fn test_strategy_option() {
+        let named = |name: &str| StrategyOption::Named(name.to_string());
+        assert_eq!(
+            DashStrategy::from_option(&StrategyOption::Enabled(true)),
+            Some(DashStrategy::SpacedHyphen)
+        );
+        assert_eq!(
+            DashStrategy::from_option(&StrategyOption::Enabled(false)),
+            None
+        );
+        assert_eq!(
+            DashStrategy::from_option(&named("comma")),
+            Some(DashStrategy::Comma)
+        );
+    }
+}
@@ -6,12 +6,6 @@ pub fn set_verbose(verbose: bool) {
     VERBOSE.store(verbose, Ordering::SeqCst);
 }
 
-pub fn debug_println(msg: &str) {
-    if VERBOSE.load(Ordering::SeqCst) {
-        println!("{}", msg);
-    }
-}
-
 pub fn debug_format(fmt: std::fmt::Arguments<'_>) {
     if VERBOSE.load(Ordering::SeqCst) {
         println!("{}", fmt);
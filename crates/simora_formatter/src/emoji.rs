@@ -0,0 +1,133 @@
+use emojis::{Emoji, SkinTone};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Whether `grapheme` is an emoji, including ZWJ sequences, skin tones, keycaps and flags.
+/// Symbols shown as text unless followed by U+FE0F, such as `©` and `™`, are not.
+pub(crate) fn is_emoji(grapheme: &str) -> bool {
+    if emojis::get(grapheme).is_none() {
+        return false;
+    }
+    let mut chars = grapheme.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c >= '\u{2300}',
+        _ => true,
+    }
+}
+
+/// Removes the emoji of `text` along with the space separating them from it
+pub(crate) fn remove_emoji(text: &str) -> String {
+    let mut result = String::new();
+    let mut graphemes = text.graphemes(true).peekable();
+    while let Some(grapheme) = graphemes.next() {
+        if !is_emoji(grapheme) {
+            result.push_str(grapheme);
+            continue;
+        }
+        let is_space = |next: &&str| next.trim().is_empty();
+        if graphemes.peek().is_some_and(is_space) {
+            if result.is_empty() || result.ends_with(char::is_whitespace) {
+                while graphemes.peek().is_some_and(is_space) {
+                    graphemes.next();
+                }
+            }
+        } else {
+            // Nor leave a space before the end of the text or closing punctuation
+            let closes = |next: &&str| next.starts_with([',', '.', ';', ':', '!', '?', ')']);
+            if graphemes.peek().is_none_or(closes) {
+                result.truncate(result.trim_end().len());
+            }
+        }
+    }
+    result
+}
+
+/// Replaces the emoji of `text` with their GitHub `:shortcode:`, keeping those without one
+pub(crate) fn to_shortcodes(text: &str) -> String {
+    text.graphemes(true)
+        .map(|grapheme| match emojis::get(grapheme).and_then(shortcode) {
+            Some(shortcode) if is_emoji(grapheme) => shortcode,
+            _ => grapheme.to_string(),
+        })
+        .collect()
+}
+
+/// The `:shortcode:` of `emoji`. Those with a skin tone are written as the shortcode of
+/// their base emoji followed by the tone, e.g. `:+1::skin-tone-4:`, while the mixed tones
+/// of couples have none.
+fn shortcode(emoji: &Emoji) -> Option<String> {
+    let tone = match emoji.skin_tone() {
+        None | Some(SkinTone::Default) => {
+            return emoji
+                .shortcode()
+                .map(|shortcode| format!(":{}:", shortcode))
+        }
+        Some(SkinTone::Light) => 2,
+        Some(SkinTone::MediumLight) => 3,
+        Some(SkinTone::Medium) => 4,
+        Some(SkinTone::MediumDark) => 5,
+        Some(SkinTone::Dark) => 6,
+        Some(_) => return None,
+    };
+    let base = emoji.with_skin_tone(SkinTone::Default)?.shortcode()?;
+    Some(format!(":{}::skin-tone-{}:", base, tone))
+}
+
+/// `text` without the emoji it starts with when they decorate it like a bullet, followed
+/// by a space and more text
+pub(crate) fn strip_leading_emoji(text: &str) -> &str {
+    let mut rest = text;
+    while let Some(grapheme) = rest.graphemes(true).next().filter(|g| is_emoji(g)) {
+        rest = &rest[grapheme.len()..];
+    }
+    let stripped = rest.trim_start();
+    if rest.len() == text.len() || stripped.len() == rest.len() || stripped.is_empty() {
+        return text;
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emoji_sequences() {
+        for emoji in ["🎯", "👩🏽‍💻", "👨‍👩‍👧", "❤️", "✔", "#️⃣", "🇬🇧", "🏳️‍🌈"]
+        {
+            assert!(is_emoji(emoji), "{}", emoji);
+        }
+        for text in ["#", "1", "©", "™", "↔", "a", "—"] {
+            assert!(!is_emoji(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_remove_emoji() {
+        assert_eq!(remove_emoji("🌟 **Partner** 🌟"), "**Partner**");
+        assert_eq!(remove_emoji("### 🎯 **CTO**"), "### **CTO**");
+        assert_eq!(remove_emoji("by 👩🏽‍💻 me™"), "by me™");
+        assert_eq!(remove_emoji("Ship it 👍🏽, now"), "Ship it, now");
+        assert_eq!(remove_emoji("see 🚀 "), "see ");
+    }
+
+    #[test]
+    fn test_shortcodes_and_bullets() {
+        assert_eq!(
+            to_shortcodes("Ship it 🚀 ✔"),
+            "Ship it :rocket: :heavy_check_mark:"
+        );
+        assert_eq!(
+            to_shortcodes("👍🏽 👋🏻 👍"),
+            ":+1::skin-tone-4: :wave::skin-tone-2: :+1:"
+        );
+        assert_eq!(to_shortcodes("👩🏿‍❤️‍👨🏼"), "👩🏿‍❤️‍👨🏼");
+        assert_eq!(strip_leading_emoji("✔ Proven"), "Proven");
+        assert_eq!(
+            strip_leading_emoji("📩 **Availability**"),
+            "**Availability**"
+        );
+        assert_eq!(strip_leading_emoji("🎉🎉 Done"), "Done");
+        assert_eq!(strip_leading_emoji("🎉"), "🎉");
+        assert_eq!(strip_leading_emoji("Done 🎉"), "Done 🎉");
+    }
+}
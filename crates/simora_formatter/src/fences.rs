@@ -0,0 +1,155 @@
+use crate::containers::split_container_markers;
+
+/// Where a line stands relative to fenced code blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FenceLine {
+    Outside,
+    Opening,
+    Inside,
+    Closing,
+}
+
+impl FenceLine {
+    /// Whether the line belongs to a code block, fences included
+    pub fn is_code(self) -> bool {
+        self != FenceLine::Outside
+    }
+}
+
+#[derive(Debug)]
+struct OpenFence {
+    character: u8,
+    length: usize,
+}
+
+/// Follows fenced code blocks line by line, as CommonMark does: a block opened by three or
+/// more backticks or tildes is closed by a fence of the same character at least as long,
+/// or runs to the end of the document
+#[derive(Debug, Default)]
+pub(crate) struct Fences {
+    open: Option<OpenFence>,
+    /// The width of the markers of the list item lines are continuing, which fences may be
+    /// indented by on top of the three spaces allowed anywhere
+    list_indent: usize,
+}
+
+impl Fences {
+    /// Classifies `line`, the lines of a document being given in order
+    pub fn classify(&mut self, line: &str) -> FenceLine {
+        let (markers, content) = split_container_markers(line);
+        let indent = content
+            .bytes()
+            .take_while(|b| matches!(b, b' ' | b'\t'))
+            .map(|b| if b == b'\t' { 4 } else { 1 })
+            .sum::<usize>();
+        let is_list_item = markers.trim_end().ends_with(|c| c != '>');
+        let max_indent = if markers.is_empty() {
+            self.list_indent
+        } else {
+            0
+        } + 3;
+        let fence = (indent <= max_indent)
+            .then(|| parse_fence(content.trim_start()))
+            .flatten();
+
+        if let Some(open) = &self.open {
+            return match fence {
+                Some((character, length, info))
+                    if character == open.character
+                        && length >= open.length
+                        && info.trim().is_empty() =>
+                {
+                    self.open = None;
+                    FenceLine::Closing
+                }
+                _ => FenceLine::Inside,
+            };
+        }
+
+        if is_list_item {
+            self.list_indent = markers.len();
+        } else if markers.is_empty() && indent == 0 && !content.trim().is_empty() {
+            self.list_indent = 0;
+        }
+        match fence {
+            // The info string of a backtick fence cannot hold backticks
+            Some((b'`', _, info)) if info.contains('`') => FenceLine::Outside,
+            Some((character, length, _)) => {
+                self.open = Some(OpenFence { character, length });
+                FenceLine::Opening
+            }
+            None => FenceLine::Outside,
+        }
+    }
+}
+
+/// The character and length of the fence `text` starts with, and the text after it
+fn parse_fence(text: &str) -> Option<(u8, usize, &str)> {
+    let character = *text
+        .as_bytes()
+        .first()
+        .filter(|c| matches!(c, b'`' | b'~'))?;
+    let length = text.bytes().take_while(|&c| c == character).count();
+    (length >= 3).then(|| (character, length, &text[length..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_lines(content: &str) -> Vec<bool> {
+        let mut fences = Fences::default();
+        content
+            .lines()
+            .map(|line| fences.classify(line).is_code())
+            .collect()
+    }
+
+    #[test]
+    fn test_fence_character_and_length() {
+        assert_eq!(
+            code_lines("````md\n```\n````\ntext"),
+            [true, true, true, false]
+        );
+        assert_eq!(
+            code_lines("~~~\n```\n~~~~\ntext"),
+            [true, true, true, false]
+        );
+        assert_eq!(code_lines("``` a`b\ntext"), [false, false]);
+    }
+
+    #[test]
+    fn test_fence_indentation() {
+        assert_eq!(
+            code_lines("   ```\ncode\n```\ntext"),
+            [true, true, true, false]
+        );
+        assert_eq!(code_lines("    ```\ntext"), [false, false]);
+        assert_eq!(
+            code_lines("- item\n\n    ```\n    code\n    ```\ntext"),
+            [false, false, true, true, true, false]
+        );
+        assert_eq!(
+            code_lines("> ```\n> code\n> ```\n> text"),
+            [true, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_fence_runs_to_the_end() {
+        let mut fences = Fences::default();
+        let lines: Vec<FenceLine> = ["```", "code", "``", "text"]
+            .iter()
+            .map(|line| fences.classify(line))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                FenceLine::Opening,
+                FenceLine::Inside,
+                FenceLine::Inside,
+                FenceLine::Inside
+            ]
+        );
+    }
+}
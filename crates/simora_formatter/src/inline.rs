@@ -0,0 +1,283 @@
+use regex::Regex;
+
+/// Autolinks such as `<https://example.com>` or `<me@example.com>`
+const AUTOLINK_PATTERN: &str = r"^<(?:[A-Za-z][A-Za-z0-9+.\-]{1,31}:[^\s<>]*|[A-Za-z0-9.!#$%&'*+/=?^_`{|}~\-]+@[A-Za-z0-9.\-]+)>";
+/// Raw HTML: tags, closing tags, comments, processing instructions and declarations
+const HTML_PATTERN: &str = r"^(?:<!--.*?-->|<\?.*?\?>|<![A-Za-z][^>]*>|</?[A-Za-z][A-Za-z0-9\-]*(?:\s+[A-Za-z_:][A-Za-z0-9_.:\-]*(?:\s*=\s*(?:[^\s\x22'=<>`]+|'[^']*'|\x22[^\x22]*\x22))?)*\s*/?>)";
+/// URLs linked without any markup, as GitHub does
+const URL_PATTERN: &str = r"^(?:(?:https?|ftp)://|www\.)[^\s<>]*[^\s<>?!.,:*_~'\x22)]";
+/// Link reference definitions, `[label]: destination "title"`
+const DEFINITION_PATTERN: &str = r"^ {0,3}\[[^\]]+\]:\s*";
+
+/// A part of a line of Markdown text
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Span<'a> {
+    /// Text that text rules may change
+    Prose(&'a str),
+    /// Inline code, autolinks, link destinations, URLs and raw HTML
+    Literal(&'a str),
+    /// `$`, `$$`, `\(` or `\[` math, with its delimiters
+    Math(&'a str),
+}
+
+impl<'a> Span<'a> {
+    /// The text of the span, as written in the line
+    pub fn text(&self) -> &'a str {
+        match self {
+            Span::Prose(text) | Span::Literal(text) | Span::Math(text) => text,
+        }
+    }
+}
+
+/// Splits lines into prose and the inline syntax text rules must leave alone
+#[derive(Debug)]
+pub(crate) struct InlineSyntax {
+    autolink: Regex,
+    html: Regex,
+    url: Regex,
+    definition: Regex,
+    /// Whether math is told apart from prose
+    math: bool,
+}
+
+impl InlineSyntax {
+    pub fn new(math: bool) -> Self {
+        Self {
+            autolink: Regex::new(AUTOLINK_PATTERN).unwrap(),
+            html: Regex::new(HTML_PATTERN).unwrap(),
+            url: Regex::new(URL_PATTERN).unwrap(),
+            definition: Regex::new(DEFINITION_PATTERN).unwrap(),
+            math,
+        }
+    }
+
+    /// Applies `format` to the prose of `line` only
+    pub fn map_prose(&self, line: &str, format: impl Fn(&str) -> String) -> String {
+        self.map_prose_at_edges(line, |text, _, _| format(text))
+    }
+
+    /// Applies `format` to the prose of `line` only, telling it whether each piece of prose
+    /// starts and ends the line rather than meeting inline syntax
+    pub fn map_prose_at_edges(
+        &self,
+        line: &str,
+        format: impl Fn(&str, bool, bool) -> String,
+    ) -> String {
+        let spans = self.spans(line);
+        let last = spans.len().saturating_sub(1);
+        spans
+            .into_iter()
+            .enumerate()
+            .map(|(i, span)| match span {
+                Span::Prose(text) => format(text, i == 0, i == last),
+                Span::Literal(text) | Span::Math(text) => text.to_string(),
+            })
+            .collect()
+    }
+
+    /// Applies `convert` to the math of `line` only
+    pub fn map_math(&self, line: &str, convert: impl Fn(&str) -> String) -> String {
+        self.spans(line)
+            .into_iter()
+            .map(|span| match span {
+                Span::Math(text) => convert(text),
+                Span::Prose(text) | Span::Literal(text) => text.to_string(),
+            })
+            .collect()
+    }
+
+    /// The spans of `line`, in order
+    pub fn spans<'a>(&self, line: &'a str) -> Vec<Span<'a>> {
+        let mut spans = Vec::new();
+        // The destination and title of a reference definition are taken as written
+        if let Some(definition) = self.definition.find(line) {
+            spans.push(Span::Prose(definition.as_str()));
+            spans.push(Span::Literal(&line[definition.end()..]));
+            return spans;
+        }
+
+        let bytes = line.as_bytes();
+        let mut prose_start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let literal_end = match bytes[i] {
+                b'\\' if self.math && matches!(bytes.get(i + 1), Some(b'(' | b'[')) => {
+                    bracket_math_end(bytes, i)
+                }
+                // An escaped character is never the start of a span
+                b'\\' => {
+                    i += line[i + 1..].chars().next().map_or(1, |c| 1 + c.len_utf8());
+                    continue;
+                }
+                b'`' => code_span_end(bytes, i),
+                b'<' => self
+                    .autolink
+                    .find(&line[i..])
+                    .or_else(|| self.html.find(&line[i..]))
+                    .map(|found| i + found.end()),
+                b']' if bytes.get(i + 1) == Some(&b'(') => {
+                    // Keep the `]` with the prose, the destination starts at `(`
+                    i += 1;
+                    closing_parenthesis(bytes, i).map(|end| end + 1)
+                }
+                b'$' if self.math => math_end(bytes, i),
+                b'h' | b'f' | b'w' if i == 0 || !bytes[i - 1].is_ascii_alphanumeric() => {
+                    self.url.find(&line[i..]).map(|found| i + found.end())
+                }
+                _ => None,
+            };
+            match literal_end {
+                Some(end) => {
+                    if prose_start < i {
+                        spans.push(Span::Prose(&line[prose_start..i]));
+                    }
+                    let literal = &line[i..end];
+                    spans.push(match bytes[i] {
+                        b'$' | b'\\' => Span::Math(literal),
+                        _ => Span::Literal(literal),
+                    });
+                    i = end;
+                    prose_start = end;
+                }
+                None => i += line[i..].chars().next().map_or(1, char::len_utf8),
+            }
+        }
+        if prose_start < line.len() {
+            spans.push(Span::Prose(&line[prose_start..]));
+        }
+        spans
+    }
+}
+
+/// The end of the code span opened by the backticks at `start`: after a run of exactly
+/// as many backticks. Unmatched backticks are literal text.
+fn code_span_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let run = |from: usize| bytes[from..].iter().take_while(|&&b| b == b'`').count();
+    let length = run(start);
+    let mut i = start + length;
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            let closing = run(i);
+            if closing == length {
+                return Some(i + closing);
+            }
+            i += closing;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// The index of the `)` closing the `(` at `start`, allowing balanced parentheses and
+/// `<...>` destinations
+fn closing_parenthesis(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_angle_brackets = false;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'<' if i == start + 1 => in_angle_brackets = true,
+            b'>' if in_angle_brackets => in_angle_brackets = false,
+            b'(' if !in_angle_brackets => depth += 1,
+            b')' if !in_angle_brackets => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The end of the math opened at `start` by `\(` or `\[`, after the matching `\)` or `\]`
+fn bracket_math_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let closing = if bytes[start + 1] == b'(' { b')' } else { b']' };
+    (start + 2..bytes.len().saturating_sub(1))
+        .find(|&i| bytes[i] == b'\\' && bytes[i + 1] == closing)
+        .map(|i| i + 2)
+}
+
+/// The end of the math opened at `start` by `$` or `$$`. As in Pandoc, inline math may not
+/// start or end with a space and the closing `$` may not be followed by a digit, so prices
+/// such as `$5 and $10` stay prose.
+fn math_end(bytes: &[u8], start: usize) -> Option<usize> {
+    if bytes.get(start + 1) == Some(&b'$') {
+        let body = start + 2;
+        return (body..bytes.len().saturating_sub(1))
+            .find(|&i| bytes[i] == b'$' && bytes[i + 1] == b'$' && bytes[i - 1] != b'\\')
+            .map(|i| i + 2);
+    }
+    let body = start + 1;
+    if bytes.get(body).is_none_or(u8::is_ascii_whitespace) {
+        return None;
+    }
+    (body + 1..bytes.len())
+        .find(|&i| {
+            bytes[i] == b'$'
+                && !bytes[i - 1].is_ascii_whitespace()
+                && bytes[i - 1] != b'\\'
+                && !bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+        })
+        .map(|i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literals(line: &str) -> Vec<&str> {
+        InlineSyntax::new(true)
+            .spans(line)
+            .into_iter()
+            .filter_map(|span| match span {
+                Span::Literal(text) | Span::Math(text) => Some(text),
+                Span::Prose(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_code_spans() {
+        assert_eq!(literals("a `b—c` d"), ["`b—c`"]);
+        assert_eq!(literals("``a ` b`` c"), ["``a ` b``"]);
+        assert!(literals("an `unclosed span").is_empty());
+        assert!(literals(r"\`not code\`").is_empty());
+    }
+
+    #[test]
+    fn test_links_and_urls() {
+        assert_eq!(
+            literals("[docs…](https://a.com/x…y \"Title\") and <https://b.com/…>"),
+            ["(https://a.com/x…y \"Title\")", "<https://b.com/…>"]
+        );
+        assert_eq!(literals("see https://a.com/a—b."), ["https://a.com/a—b"]);
+        assert_eq!(
+            literals("[a]: https://a.com/… “t”"),
+            ["https://a.com/… “t”"]
+        );
+        assert!(literals("the shape of a cup").is_empty());
+    }
+
+    #[test]
+    fn test_html_and_math() {
+        assert_eq!(
+            literals("<span title=“x”>a—b</span>"),
+            ["<span title=“x”>", "</span>"]
+        );
+        assert_eq!(literals("if a < b then"), Vec::<&str>::new());
+        assert_eq!(literals("$a–b$ and $$x…y$$"), ["$a–b$", "$$x…y$$"]);
+        assert!(literals("costs $5 and $10").is_empty());
+        assert_eq!(
+            literals(r"so \(a–b\) and \[x…y\]"),
+            [r"\(a–b\)", r"\[x…y\]"]
+        );
+        assert!(literals(r"an escaped \( paren").is_empty());
+        let spans = InlineSyntax::new(false).spans("$a–b$");
+        assert_eq!(spans, [Span::Prose("$a–b$")]);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::emoji::is_emoji;
+use simora_configuration::InvisibleCharactersConfig;
+use std::fmt;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The classes of characters handled by the `invisible_characters` rule, each with its own
+/// option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Space,
+    Hyphen,
+    ZeroWidth,
+    SoftHyphen,
+    ByteOrderMark,
+}
+
+impl Class {
+    fn is_enabled(self, config: &InvisibleCharactersConfig) -> bool {
+        let option = match self {
+            Class::Space => config.spaces,
+            Class::Hyphen => config.hyphens,
+            Class::ZeroWidth => config.zero_width,
+            Class::SoftHyphen => config.soft_hyphens,
+            Class::ByteOrderMark => config.byte_order_marks,
+        };
+        option.unwrap_or_default()
+    }
+
+    fn replacement(self) -> &'static str {
+        match self {
+            Class::Space => " ",
+            Class::Hyphen => "-",
+            Class::ZeroWidth | Class::SoftHyphen | Class::ByteOrderMark => "",
+        }
+    }
+}
+
+/// Every character the rule handles, with its Unicode name
+const CHARACTERS: &[(char, &str, Class)] = &[
+    ('\u{00A0}', "no-break space", Class::Space),
+    ('\u{2000}', "en quad", Class::Space),
+    ('\u{2001}', "em quad", Class::Space),
+    ('\u{2002}', "en space", Class::Space),
+    ('\u{2003}', "em space", Class::Space),
+    ('\u{2004}', "three-per-em space", Class::Space),
+    ('\u{2005}', "four-per-em space", Class::Space),
+    ('\u{2006}', "six-per-em space", Class::Space),
+    ('\u{2007}', "figure space", Class::Space),
+    ('\u{2008}', "punctuation space", Class::Space),
+    ('\u{2009}', "thin space", Class::Space),
+    ('\u{200A}', "hair space", Class::Space),
+    ('\u{202F}', "narrow no-break space", Class::Space),
+    ('\u{205F}', "medium mathematical space", Class::Space),
+    ('\u{2010}', "hyphen", Class::Hyphen),
+    ('\u{2011}', "non-breaking hyphen", Class::Hyphen),
+    ('\u{200B}', "zero width space", Class::ZeroWidth),
+    ('\u{200C}', "zero width non-joiner", Class::ZeroWidth),
+    ('\u{200D}', "zero width joiner", Class::ZeroWidth),
+    ('\u{2060}', "word joiner", Class::ZeroWidth),
+    ('\u{00AD}', "soft hyphen", Class::SoftHyphen),
+    ('\u{FEFF}', "byte order mark", Class::ByteOrderMark),
+];
+
+/// An invisible or non-standard character the `invisible_characters` rule changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvisibleCharacter {
+    /// 1-based line and column, counted in characters
+    pub line: usize,
+    pub column: usize,
+    pub character: char,
+    pub name: &'static str,
+}
+
+impl fmt::Display for InvisibleCharacter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "U+{:04X} {}", self.character as u32, self.name)
+    }
+}
+
+/// The characters of `line` to change, as their byte index, name and replacement.
+/// Joiners are kept inside emoji sequences and between letters of scripts shaped by them.
+fn find(
+    line: &str,
+    config: &InvisibleCharactersConfig,
+) -> Vec<(usize, &'static str, &'static str)> {
+    let mut found = Vec::new();
+    for (start, grapheme) in line.grapheme_indices(true) {
+        if is_emoji(grapheme) {
+            continue;
+        }
+        for (offset, c) in grapheme.char_indices() {
+            let Some((_, name, class)) = CHARACTERS.iter().find(|(candidate, ..)| *candidate == c)
+            else {
+                continue;
+            };
+            if !class.is_enabled(config) {
+                continue;
+            }
+            let index = start + offset;
+            if matches!(c, '\u{200C}' | '\u{200D}') {
+                let is_letter =
+                    |c: Option<char>| c.is_some_and(|c| c.is_alphabetic() && !c.is_ascii());
+                let before = line[..index].chars().next_back();
+                let after = line[index + c.len_utf8()..].chars().next();
+                if is_letter(before) && is_letter(after) {
+                    continue;
+                }
+            }
+            found.push((index, *name, class.replacement()));
+        }
+    }
+    found
+}
+
+/// Normalises or removes the characters of `line` enabled in `config`
+pub(crate) fn normalize(line: &str, config: &InvisibleCharactersConfig) -> String {
+    let mut result = String::new();
+    let mut end = 0;
+    for (index, _, replacement) in find(line, config) {
+        result.push_str(&line[end..index]);
+        result.push_str(replacement);
+        end = index + line[index..].chars().next().map_or(0, char::len_utf8);
+    }
+    result.push_str(&line[end..]);
+    result
+}
+
+/// The characters `normalize` changes in the `prose` byte range of `line`, the 0-based
+/// `index`th of its document
+pub(crate) fn locate(
+    line: &str,
+    index: usize,
+    prose: Range<usize>,
+    config: &InvisibleCharactersConfig,
+) -> Vec<InvisibleCharacter> {
+    find(&line[prose.clone()], config)
+        .into_iter()
+        .map(|(byte, name, _)| {
+            let byte = prose.start + byte;
+            InvisibleCharacter {
+                line: index + 1,
+                column: line[..byte].chars().count() + 1,
+                character: line[byte..].chars().next().unwrap_or_default(),
+                name,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_classes() -> InvisibleCharactersConfig {
+        InvisibleCharactersConfig {
+            enabled: Some(true),
+            spaces: Some(true),
+            hyphens: Some(true),
+            zero_width: Some(true),
+            soft_hyphens: Some(true),
+            byte_order_marks: Some(true),
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let config = all_classes();
+        assert_eq!(
+            normalize("\u{FEFF}10\u{202F}km,\u{00A0}e\u{2011}mail", &config),
+            "10 km, e-mail"
+        );
+        assert_eq!(normalize("in\u{00AD}vis\u{200B}ible", &config), "invisible");
+        // Joiners inside emoji and Persian words are meaningful
+        assert_eq!(
+            normalize("👩‍💻 می\u{200C}خواهم", &config),
+            "👩‍💻 می\u{200C}خواهم"
+        );
+        assert_eq!(normalize("a\u{200D}b", &config), "ab");
+
+        let config = InvisibleCharactersConfig {
+            spaces: Some(false),
+            ..all_classes()
+        };
+        assert_eq!(normalize("10\u{202F}km\u{200B}", &config), "10\u{202F}km");
+    }
+
+    #[test]
+    fn test_locate() {
+        let line = "Prix\u{202F}: 5\u{00A0}€";
+        let found = locate(line, 2, 0..line.len(), &all_classes());
+        let described: Vec<String> = found
+            .iter()
+            .map(|c| format!("{}:{} {}", c.line, c.column, c))
+            .collect();
+        assert_eq!(
+            described,
+            [
+                "3:5 U+202F narrow no-break space",
+                "3:9 U+00A0 no-break space"
+            ]
+        );
+    }
+}
@@ -1,14 +1,39 @@
 use regex::Regex;
 use simora_configuration::{
-    global_config::get_markdown_config, HeadingsConfig, HorizontalRulesConfig,
-    MarkdownFormatterConfig, PartialMarkdownFormatterConfiguration, PunctuationConfig, RulesConfig,
-    SmartQuotesConfig,
+    global_config::get_markdown_config, EmojiConfig, HeadingsConfig, HorizontalRulesConfig,
+    InvisibleCharactersConfig, MarkdownFormatterConfig, MathConfig, Merge,
+    PartialMarkdownFormatterConfiguration, PunctuationConfig, ReasoningBlocksConfig, RulesConfig,
+    SmartQuotesConfig, StrategyOption,
 };
 use std::error::Error;
 use std::fmt;
 
+mod containers;
+mod dashes;
 mod debug;
+mod emoji;
+mod fences;
+mod inline;
+mod invisible;
+mod math;
+mod quotes;
+mod reasoning;
+mod suppressions;
+mod whitespace;
+use containers::split_container_markers;
+use dashes::DashStrategy;
 pub use debug::set_verbose;
+use fences::Fences;
+use inline::{InlineSyntax, Span};
+pub use invisible::InvisibleCharacter;
+use math::{MathBlocks, MathLine, MathStyle};
+use suppressions::Suppressions;
+pub use suppressions::{Directive, DirectiveKind};
+
+/// The suppression comments of `content`, in order
+pub fn parse_suppressions(content: &str) -> Vec<Directive> {
+    Suppressions::parse(content).directives
+}
 
 #[derive(Debug)]
 pub enum FormatterError {
@@ -38,55 +63,107 @@ pub trait Formatter {
     ) -> Result<(), FormatterError>;
 }
 
+/// Rule names, as used in configuration and suppression comments
+const RULE_SMART_QUOTES: &str = "smart_quotes";
+const RULE_HEADINGS: &str = "headings";
+const RULE_HORIZONTAL_RULES: &str = "remove_horizontal_rules";
+const RULE_PUNCTUATION: &str = "punctuation";
+const RULE_MATH: &str = "math";
+const RULE_EMOJI: &str = "emoji";
+const RULE_INVISIBLE_CHARACTERS: &str = "invisible_characters";
+const RULE_REASONING_BLOCKS: &str = "reasoning_blocks";
+
 /// A basic Markdown formatter
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MarkdownFormatter {
     config: Option<MarkdownFormatterConfig>,
 }
 
-impl Default for MarkdownFormatter {
-    fn default() -> Self {
-        Self { config: None }
-    }
-}
-
 impl MarkdownFormatter {
     pub fn new() -> Self {
-        let default_config = MarkdownFormatterConfig {
-            enabled: true,
+        let mut config = Self::default_config();
+        if let Some(markdown) = get_markdown_config() {
+            config.merge_with(markdown.clone());
+        }
+
+        Self {
+            config: Some(config),
+        }
+    }
+
+    /// The configuration used for any option not set by the user
+    pub fn default_config() -> MarkdownFormatterConfig {
+        MarkdownFormatterConfig {
+            enabled: Some(true),
             rules: RulesConfig {
-                smart_quotes: SmartQuotesConfig { enabled: true },
+                smart_quotes: SmartQuotesConfig {
+                    enabled: Some(true),
+                    low_quotes: Some(true),
+                    guillemets: Some(true),
+                    modifier_apostrophes: Some(true),
+                    full_width: Some(true),
+                    primes: Some(true),
+                },
                 headings: HeadingsConfig {
-                    enabled: true,
-                    remove_emphasis: true,
+                    enabled: Some(true),
+                    remove_emphasis: None,
                 },
                 remove_horizontal_rules: HorizontalRulesConfig {
-                    enabled: true,
-                    retain_frontmatter_wrappers: true,
+                    enabled: Some(true),
+                    retain_frontmatter_wrappers: Some(true),
                 },
                 punctuation: PunctuationConfig {
-                    enabled: true,
-                    standardize_dashes: true,
-                    standardize_ellipsis: true,
+                    enabled: Some(true),
+                    standardize_dashes: Some(StrategyOption::Enabled(true)),
+                    standardize_ellipsis: Some(true),
+                },
+                math: MathConfig {
+                    enabled: Some(true),
+                    delimiters: None,
+                },
+                emoji: EmojiConfig {
+                    enabled: Some(false),
+                    strip_headings: Some(true),
+                    strip_bullets: Some(false),
+                    body_text: Some("keep".to_string()),
+                },
+                invisible_characters: InvisibleCharactersConfig {
+                    enabled: Some(false),
+                    spaces: Some(true),
+                    hyphens: Some(true),
+                    zero_width: Some(true),
+                    soft_hyphens: Some(true),
+                    byte_order_marks: Some(true),
+                },
+                reasoning_blocks: ReasoningBlocksConfig {
+                    enabled: Some(false),
                 },
             },
-        };
+            // Line endings and whitespace are preserved unless configured
+            ..Default::default()
+        }
+    }
 
-        Self {
-            config: Some(get_markdown_config().cloned().unwrap_or(default_config)),
+    /// The configured `max_line_length`, if any
+    pub fn max_line_length(&self) -> Option<usize> {
+        let config = self.config.as_ref()?;
+        config.max_line_length.map(|max| max as usize)
+    }
+
+    /// Numbers of the lines longer than `max_line_length`, if it is set
+    pub fn long_lines(&self, content: &str) -> Vec<usize> {
+        match self.max_line_length() {
+            Some(max) => whitespace::long_lines(content, max),
+            None => Vec::new(),
         }
     }
 
     fn format_smart_quotes(&self, content: &str) -> String {
         if let Some(config) = &self.config {
-            if config.rules.smart_quotes.enabled {
+            if config.rules.smart_quotes.enabled.unwrap_or_default() {
                 debug!("Applying smart quotes formatting");
                 // Replace smart quotes with straight quotes
-                content
-                    .replace('\u{201c}', "\"") // Left double quote
-                    .replace('\u{201d}', "\"") // Right double quote
-                    .replace('\u{2018}', "'") // Left single quote
-                    .replace('\u{2019}', "'") // Right single quote
+                quotes::straighten(content, &config.rules.smart_quotes)
             } else {
                 content.to_string()
             }
@@ -97,9 +174,12 @@ impl MarkdownFormatter {
 
     fn format_headings(&self, content: &str) -> String {
         if let Some(config) = &self.config {
-            if config.rules.headings.enabled && config.rules.headings.remove_emphasis {
+            let headings = &config.rules.headings;
+            // `remove_emphasis` is deprecated, only an explicit `false` still disables the rule
+            if headings.enabled.unwrap_or_default() && headings.remove_emphasis.unwrap_or(true) {
                 debug!("Applying headings formatting");
-                let heading_pattern = Regex::new(r"^(#+)\s+\*\*(.*?)\*\*$").unwrap();
+                // Spacing inside the emphasis, such as that of a spaced dash, is dropped with it
+                let heading_pattern = Regex::new(r"^(#+)\s+\*\*\s*(.*?)\s*\*\*$").unwrap();
                 let lines: Vec<String> = content
                     .lines()
                     .map(|line| {
@@ -120,9 +200,14 @@ impl MarkdownFormatter {
         }
     }
 
-    fn remove_horizontal_rules(&self, content: &str, in_code_block: bool) -> String {
+    fn remove_horizontal_rules(&self, content: &str, suppressions: &mut Suppressions) -> String {
         if let Some(config) = &self.config {
-            if config.rules.remove_horizontal_rules.enabled {
+            if config
+                .rules
+                .remove_horizontal_rules
+                .enabled
+                .unwrap_or_default()
+            {
                 debug!("Applying horizontal rules formatting");
                 let hr_pattern = Regex::new(r"(?m)^\s*---\s*$").unwrap();
                 let mut result = Vec::new();
@@ -142,12 +227,21 @@ impl MarkdownFormatter {
                 // Check for front matter
                 let mut in_frontmatter = false;
                 let mut frontmatter_start = false;
-                let retain_frontmatter = config.rules.remove_horizontal_rules.retain_frontmatter_wrappers;
+                let retain_frontmatter = config
+                    .rules
+                    .remove_horizontal_rules
+                    .retain_frontmatter_wrappers
+                    .unwrap_or_default();
 
                 // Handle leading horizontal rule (potential front matter start)
-                if !lines.is_empty() && hr_pattern.is_match(lines[0]) {
+                // A suppressed leading rule is kept by the loop below
+                if !lines.is_empty()
+                    && hr_pattern.is_match(lines[0])
+                    && !suppressions.suppresses(0, RULE_HORIZONTAL_RULES, true)
+                {
                     if retain_frontmatter {
-                        // Only treat it as frontmatter if there's content between the markers
+                        // Only treat it as frontmatter if there's YAML content between the markers
+                        let yaml_key_pattern = Regex::new(r"^[\w-]+\s*:").unwrap();
                         let mut has_content = false;
                         let mut found_end = false;
                         for line in lines.iter().skip(1) {
@@ -155,7 +249,7 @@ impl MarkdownFormatter {
                                 found_end = true;
                                 break;
                             }
-                            if !line.trim().is_empty() {
+                            if yaml_key_pattern.is_match(line) {
                                 has_content = true;
                             }
                         }
@@ -173,15 +267,45 @@ impl MarkdownFormatter {
                     }
                 }
 
-                for (i, line) in lines.iter().enumerate().skip(if frontmatter_start { 1 } else { 0 }) {
-                    if line.starts_with("```")
-                        || line.starts_with("    ")
-                        || line.starts_with("\t")
-                        || line.starts_with(">")
+                let mut fences = Fences::default();
+                for (i, line) in
+                    lines
+                        .iter()
+                        .enumerate()
+                        .skip(if frontmatter_start { 1 } else { 0 })
+                {
+                    // Rules in blockquotes are removed too, the `>` markers being kept
+                    let (markers, text) = split_container_markers(line);
+                    let quote = markers.trim_end();
+                    let in_blockquote = quote.starts_with('>')
+                        && quote.chars().all(|c| matches!(c, '>' | ' ' | '\t'));
+                    if fences.classify(line).is_code()
+                        || text.starts_with("    ")
+                        || text.starts_with("\t")
+                        || (!markers.is_empty() && !in_blockquote)
                     {
                         // Preserve special blocks exactly
                         result.push((*line).to_string());
                         prev_was_empty = false;
+                    } else if in_blockquote && hr_pattern.is_match(text) {
+                        debug!("Found horizontal rule in blockquote: {}", line);
+                        let in_quote =
+                            |other: &str| split_container_markers(other).0.starts_with(quote);
+                        if suppressions.suppresses(i, RULE_HORIZONTAL_RULES, true) {
+                            result.push((*line).to_string());
+                            prev_was_empty = false;
+                        } else if result.last().is_some_and(|prev| in_quote(prev))
+                            && lines.get(i + 1).is_some_and(|next| in_quote(next))
+                        {
+                            // Within the quote the rule becomes one of its blank lines
+                            if result.last().map(String::as_str) != Some(quote) {
+                                result.push(quote.to_string());
+                            }
+                            prev_was_empty = false;
+                        } else if !prev_was_empty && i > 0 {
+                            result.push(String::new());
+                            prev_was_empty = true;
+                        }
                     } else if hr_pattern.is_match(line) {
                         debug!("Found horizontal rule: {}", line);
                         if in_frontmatter {
@@ -190,7 +314,10 @@ impl MarkdownFormatter {
                                 result.push((*line).to_string());
                             }
                             in_frontmatter = false;
-                        } else if !in_code_block && !prev_was_empty && i > 0 {
+                        } else if suppressions.suppresses(i, RULE_HORIZONTAL_RULES, true) {
+                            result.push((*line).to_string());
+                            prev_was_empty = false;
+                        } else if !prev_was_empty && i > 0 {
                             result.push(String::new());
                             prev_was_empty = true;
                         }
@@ -211,18 +338,25 @@ impl MarkdownFormatter {
         content.to_string()
     }
 
-    fn format_punctuation(&self, content: &str) -> String {
+    /// Formats the punctuation of `content`, a piece of prose starting and ending its line
+    /// as told, which decides how dashes at its edges are replaced
+    fn format_punctuation(&self, content: &str, starts_line: bool, ends_line: bool) -> String {
         if let Some(config) = &self.config {
-            if config.rules.punctuation.enabled {
+            if config.rules.punctuation.enabled.unwrap_or_default() {
                 debug!("Applying punctuation formatting");
                 let mut result = content.to_string();
 
-                if config.rules.punctuation.standardize_dashes {
-                    // Convert em-dashes and en-dashes to hyphens
-                    result = result.replace('—', "-").replace('–', "-");
+                let option = config.rules.punctuation.standardize_dashes.as_ref();
+                if let Some(strategy) = option.and_then(DashStrategy::from_option) {
+                    result = dashes::replace_dashes(&result, strategy, starts_line, ends_line);
                 }
 
-                if config.rules.punctuation.standardize_ellipsis {
+                if config
+                    .rules
+                    .punctuation
+                    .standardize_ellipsis
+                    .unwrap_or_default()
+                {
                     // Convert ellipsis character to three dots
                     result = result.replace('…', "...");
                 }
@@ -236,12 +370,139 @@ impl MarkdownFormatter {
         }
     }
 
-    fn is_unformatted_block_start(line: &str) -> bool {
-        // Only code blocks should trigger the unformatted block state
-        line.trim_start().starts_with("```")
+    /// The `invisible_characters` options, if the rule is enabled
+    fn invisible_characters_config(&self) -> Option<&InvisibleCharactersConfig> {
+        let invisible = &self.config.as_ref()?.rules.invisible_characters;
+        invisible.enabled.unwrap_or_default().then_some(invisible)
+    }
+
+    /// The characters the `invisible_characters` rule changes in the prose of `content`,
+    /// outside of lines where it is suppressed. Nothing is found unless the formatter and
+    /// the rule are enabled.
+    pub fn invisible_characters(&self, content: &str) -> Vec<InvisibleCharacter> {
+        let Some(config) = &self.config else {
+            return Vec::new();
+        };
+        let Some(invisible) = self.invisible_characters_config() else {
+            return Vec::new();
+        };
+        if !config.enabled.unwrap_or_default() {
+            return Vec::new();
+        }
+        let math = config.rules.math.enabled.unwrap_or_default();
+        let inline = InlineSyntax::new(math);
+        let mut suppressions = Suppressions::parse(content);
+        let mut fences = Fences::default();
+        let mut math_blocks = MathBlocks::default();
+        let mut found = Vec::new();
+        for (index, line) in content.split('\n').enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if fences.classify(line).is_code() {
+                continue;
+            }
+            let (markers, content) = split_container_markers(line);
+            if (math && math_blocks.classify(content) != MathLine::Outside)
+                || suppressions.suppresses(index, RULE_INVISIBLE_CHARACTERS, false)
+            {
+                continue;
+            }
+            let mut start = markers.len();
+            for span in inline.spans(content) {
+                let end = start + span.text().len();
+                if let Span::Prose(_) = span {
+                    let prose = start..end;
+                    found.extend(invisible::locate(line, index, prose, invisible));
+                }
+                start = end;
+            }
+        }
+        found
+    }
+
+    fn format_emoji(&self, inline: &InlineSyntax, content: &str) -> String {
+        let Some(config) = &self.config else {
+            return content.to_string();
+        };
+        let emoji = &config.rules.emoji;
+        if !emoji.enabled.unwrap_or_default() {
+            return content.to_string();
+        }
+        debug!("Applying emoji formatting");
+        let heading_pattern = Regex::new(r"^ {0,3}#{1,6}(\s|$)").unwrap();
+        let is_heading = heading_pattern.is_match(content);
+        let mut result = content.to_string();
+        if is_heading && emoji.strip_headings.unwrap_or_default() {
+            let stripped = inline.map_prose(&result, emoji::remove_emoji);
+            // A heading made only of emoji is kept rather than emptied
+            if !stripped.trim_start_matches([' ', '#']).is_empty() {
+                result = stripped;
+            }
+        } else if emoji.strip_bullets.unwrap_or_default() {
+            result = emoji::strip_leading_emoji(&result).to_string();
+        }
+        match emoji.body_text.as_deref() {
+            Some("remove") => inline.map_prose(&result, emoji::remove_emoji),
+            Some("shortcode") => inline.map_prose(&result, emoji::to_shortcodes),
+            _ => result,
+        }
     }
 
-    fn format_content_once(&self, content: &str) -> Result<String, FormatterError> {
+    /// The delimiters math is rewritten with, if the `math` rule converts them
+    fn math_style(&self) -> Option<MathStyle> {
+        let math = &self.config.as_ref()?.rules.math;
+        if !math.enabled.unwrap_or_default() {
+            return None;
+        }
+        MathStyle::parse(math.delimiters.as_deref()?)
+    }
+
+    /// `content` without its reasoning blocks, if the `reasoning_blocks` rule is enabled,
+    /// and the id in `content` of each suppression comment left. Blocks are removed once,
+    /// before the other rules, as they span several lines.
+    fn remove_reasoning_blocks(
+        &self,
+        content: &str,
+        used: &mut Vec<bool>,
+    ) -> (String, Vec<Option<usize>>) {
+        let mut suppressions = Suppressions::parse(content);
+        let enabled = self
+            .config
+            .as_ref()
+            .and_then(|config| config.rules.reasoning_blocks.enabled);
+        if !enabled.unwrap_or_default() {
+            let ids = (0..suppressions.directives.len()).map(Some).collect();
+            return (content.to_string(), ids);
+        }
+        debug!("Removing reasoning blocks");
+        let (result, origins) = reasoning::remove_reasoning_blocks(content, |index| {
+            suppressions.suppresses(index, RULE_REASONING_BLOCKS, true)
+        });
+        used.resize(used.len().max(suppressions.used().len()), false);
+        for (used, suppressed) in used.iter_mut().zip(suppressions.used()) {
+            *used |= suppressed;
+        }
+        // Comments removed with a block shift the ids of those after them
+        let ids = Suppressions::parse(&result)
+            .directives
+            .iter()
+            .map(|directive| {
+                let line = origins.get(directive.line - 1)? + 1;
+                suppressions
+                    .directives
+                    .iter()
+                    .position(|original| original.line == line)
+            })
+            .collect();
+        (result, ids)
+    }
+
+    /// Formats `content` once, marking in `used` the suppression comments that kept a rule
+    /// from changing something
+    fn format_content_once(
+        &self,
+        content: &str,
+        used: &mut Vec<bool>,
+    ) -> Result<String, FormatterError> {
         // Early return for empty content
         if content.is_empty() {
             return Ok(String::new());
@@ -257,13 +518,18 @@ impl MarkdownFormatter {
             FormatterError::ConfigurationError("Configuration is not set.".to_string())
         })?;
 
-        if !config.enabled {
+        if !config.enabled.unwrap_or_default() {
             return Ok(content.to_string());
         }
 
         debug!("\nStarting format_content_once");
+        let mut suppressions = Suppressions::parse(content);
+        let math = config.rules.math.enabled.unwrap_or_default();
+        let math_style = self.math_style();
+        let inline = InlineSyntax::new(math);
         let mut result = Vec::new();
-        let mut in_code_block = false;
+        let mut fences = Fences::default();
+        let mut math_blocks = MathBlocks::default();
         let mut lines = Vec::new();
         let mut current_line = String::new();
 
@@ -285,52 +551,103 @@ impl MarkdownFormatter {
             lines.push((current_line, ""));
         }
 
-        for (i, (line, ending)) in lines.iter().enumerate() {
-            // Toggle code block state
-            if Self::is_unformatted_block_start(line) {
-                debug!("Found code block start: {}", line);
-                result.push(format!("{}{}", line, ending));
-                in_code_block = !in_code_block;
-                continue;
-            }
-
-            if in_code_block {
+        for (index, (line, ending)) in lines.iter().enumerate() {
+            if fences.classify(line).is_code() {
                 debug!("In code block, preserving line: {}", line);
                 result.push(format!("{}{}", line, ending));
                 continue;
             }
 
-            // Handle blockquotes line by line
-            if line.starts_with(">") {
-                debug!("Found blockquote: {}", line);
-                result.push(format!("{}{}", line, ending));
-                continue;
+            // Rules apply to the content of blockquotes, list items and callouts, whose
+            // markers are kept as written
+            let (markers, content) = split_container_markers(line);
+
+            // Display math between `$$` or `\[` and `\]` lines is left as written, only
+            // its delimiters being converted
+            let math_line = if math {
+                math_blocks.classify(content)
+            } else {
+                MathLine::Outside
+            };
+            match (math_line, math_style) {
+                (MathLine::Outside, _) => {}
+                (MathLine::Opening | MathLine::Closing, Some(style)) => {
+                    let converted = math::convert_delimiter_line(content, math_line, style);
+                    let converted = suppressions.apply(index, RULE_MATH, content, converted);
+                    result.push(format!("{}{}{}", markers, converted, ending));
+                    continue;
+                }
+                _ => {
+                    result.push(format!("{}{}", line, ending));
+                    continue;
+                }
             }
 
-            // Apply formatting to regular content
-            let mut formatted = line.to_string();
+            // Apply formatting to regular content, text rules only changing its prose
+            let mut formatted = content.to_string();
             debug!("Formatting line: {}", line);
-            formatted = self.format_punctuation(&formatted);
-            formatted = self.format_smart_quotes(&formatted);
-            formatted = self.format_headings(&formatted);
-            result.push(format!("{}{}", formatted, ending));
+            if let Some(style) = math_style {
+                let converted = inline.map_math(&formatted, |text| math::convert(text, style));
+                formatted = suppressions.apply(index, RULE_MATH, &formatted, converted);
+            }
+            if let Some(config) = self.invisible_characters_config() {
+                let normalized =
+                    inline.map_prose(&formatted, |text| invisible::normalize(text, config));
+                formatted =
+                    suppressions.apply(index, RULE_INVISIBLE_CHARACTERS, &formatted, normalized);
+            }
+            let emoji = self.format_emoji(&inline, &formatted);
+            formatted = suppressions.apply(index, RULE_EMOJI, &formatted, emoji);
+            let punctuated = inline.map_prose_at_edges(&formatted, |text, starts, ends| {
+                self.format_punctuation(text, starts, ends)
+            });
+            formatted = suppressions.apply(index, RULE_PUNCTUATION, &formatted, punctuated);
+            let quoted = inline.map_prose(&formatted, |text| self.format_smart_quotes(text));
+            formatted = suppressions.apply(index, RULE_SMART_QUOTES, &formatted, quoted);
+            let heading = self.format_headings(&formatted);
+            formatted = suppressions.apply(index, RULE_HEADINGS, &formatted, heading);
+            result.push(format!("{}{}{}", markers, formatted, ending));
         }
 
         let mut content = result.join("");
 
-        // Only apply horizontal rule processing if not in a code block
-        if !in_code_block {
-            debug!("Applying horizontal rule processing");
-            content = self.remove_horizontal_rules(&content, in_code_block);
+        debug!("Applying horizontal rule processing");
+        content = self.remove_horizontal_rules(&content, &mut suppressions);
+
+        used.resize(used.len().max(suppressions.used().len()), false);
+        for (used, suppressed) in used.iter_mut().zip(suppressions.used()) {
+            *used |= suppressed;
         }
 
         debug!("Finished format_content_once");
         Ok(content)
     }
-}
 
-impl Formatter for MarkdownFormatter {
-    fn format_content(&self, content: &str) -> Result<String, FormatterError> {
+    /// The suppression comments of `content` that did not keep any rule from changing it.
+    /// `ripari-enable` comments are never reported.
+    pub fn unused_suppressions(&self, content: &str) -> Result<Vec<Directive>, FormatterError> {
+        let enabled = self.config.as_ref().and_then(|config| config.enabled);
+        if !enabled.unwrap_or_default() {
+            return Ok(Vec::new());
+        }
+        let mut used = Vec::new();
+        self.format_tracked(content, &mut used)?;
+        let directives = Suppressions::parse(content).directives;
+        Ok(directives
+            .into_iter()
+            .enumerate()
+            .filter(|(id, directive)| {
+                directive.kind != DirectiveKind::Enable && !used.get(*id).copied().unwrap_or(false)
+            })
+            .map(|(_, directive)| directive)
+            .collect())
+    }
+
+    fn format_tracked(
+        &self,
+        content: &str,
+        used: &mut Vec<bool>,
+    ) -> Result<String, FormatterError> {
         // Early return for empty content
         if content.is_empty() {
             return Ok(String::new());
@@ -350,20 +667,54 @@ impl Formatter for MarkdownFormatter {
             }
         };
 
-        if !config.enabled {
+        if !config.enabled.unwrap_or_default() {
             debug!("Formatter is disabled!");
             return Ok(content.to_string());
         }
 
         debug!("Formatter configuration:");
-        debug!("  Smart quotes enabled: {}", config.rules.smart_quotes.enabled);
-        debug!("  Headings enabled: {}", config.rules.headings.enabled);
-        debug!("  Remove horizontal rules enabled: {}", config.rules.remove_horizontal_rules.enabled);
-        debug!("  Punctuation enabled: {}", config.rules.punctuation.enabled);
-        debug!("  Standardize dashes: {}", config.rules.punctuation.standardize_dashes);
-        debug!("  Standardize ellipsis: {}", config.rules.punctuation.standardize_ellipsis);
-
-        let mut current = content.to_string();
+        let rules = &config.rules;
+        debug!(
+            "  Smart quotes enabled: {}",
+            rules.smart_quotes.enabled.unwrap_or_default()
+        );
+        debug!(
+            "  Headings enabled: {}",
+            rules.headings.enabled.unwrap_or_default()
+        );
+        debug!(
+            "  Remove horizontal rules enabled: {}",
+            rules.remove_horizontal_rules.enabled.unwrap_or_default()
+        );
+        debug!(
+            "  Punctuation enabled: {}",
+            rules.punctuation.enabled.unwrap_or_default()
+        );
+        debug!(
+            "  Standardize dashes: {:?}",
+            rules.punctuation.standardize_dashes
+        );
+        debug!(
+            "  Standardize ellipsis: {}",
+            rules.punctuation.standardize_ellipsis.unwrap_or_default()
+        );
+        debug!("  Math enabled: {}", rules.math.enabled.unwrap_or_default());
+        debug!(
+            "  Emoji enabled: {}",
+            rules.emoji.enabled.unwrap_or_default()
+        );
+        debug!(
+            "  Invisible characters enabled: {}",
+            rules.invisible_characters.enabled.unwrap_or_default()
+        );
+        debug!(
+            "  Reasoning blocks enabled: {}",
+            rules.reasoning_blocks.enabled.unwrap_or_default()
+        );
+
+        let (mut current, ids) = self.remove_reasoning_blocks(content, used);
+        // Suppression comments used by the other rules, by their id in `current`
+        let mut used_after = Vec::new();
         let mut previous;
         let mut iterations = 0;
         const MAX_ITERATIONS: usize = 100; // Prevent infinite loops
@@ -371,10 +722,10 @@ impl Formatter for MarkdownFormatter {
         loop {
             previous = current.clone();
             // First, process the entire content as a whole
-            let intermediate = self.format_content_once(&previous)?;
+            let intermediate = self.format_content_once(&previous, &mut used_after)?;
 
             // Then process again to handle any new line situations
-            current = self.format_content_once(&intermediate)?;
+            current = self.format_content_once(&intermediate, &mut used_after)?;
 
             iterations += 1;
             if current == previous || iterations >= MAX_ITERATIONS {
@@ -386,15 +737,31 @@ impl Formatter for MarkdownFormatter {
                 break;
             }
         }
+        for (id, _) in used_after.iter().enumerate().filter(|(_, used)| **used) {
+            if let Some(&Some(id)) = ids.get(id) {
+                used.resize(used.len().max(id + 1), false);
+                used[id] = true;
+            }
+        }
 
-        Ok(current)
+        Ok(whitespace::normalize_whitespace(&current, config))
+    }
+}
+
+impl Formatter for MarkdownFormatter {
+    fn format_content(&self, content: &str) -> Result<String, FormatterError> {
+        self.format_tracked(content, &mut Vec::new())
     }
 
     fn apply_configuration(
         &mut self,
         config: &PartialMarkdownFormatterConfiguration,
     ) -> Result<(), FormatterError> {
-        self.config = config.markdown.clone();
+        self.config = config.markdown.clone().map(|markdown| {
+            let mut resolved = Self::default_config();
+            resolved.merge_with(markdown);
+            resolved
+        });
         Ok(())
     }
 }
@@ -411,26 +778,58 @@ mod tests {
         PartialMarkdownFormatterConfiguration {
             root: false,
             markdown: Some(MarkdownFormatterConfig {
-                enabled: true,
+                enabled: Some(true),
                 rules: RulesConfig {
-                    smart_quotes: SmartQuotesConfig { enabled: true },
+                    smart_quotes: SmartQuotesConfig {
+                        enabled: Some(true),
+                        low_quotes: Some(true),
+                        guillemets: Some(true),
+                        modifier_apostrophes: Some(true),
+                        full_width: Some(true),
+                        primes: Some(true),
+                    },
                     headings: HeadingsConfig {
-                        enabled: true,
-                        remove_emphasis: true,
+                        enabled: Some(true),
+                        remove_emphasis: Some(true),
                     },
                     remove_horizontal_rules: HorizontalRulesConfig {
-                        enabled: true,
-                        retain_frontmatter_wrappers: true,
+                        enabled: Some(true),
+                        retain_frontmatter_wrappers: Some(true),
                     },
                     punctuation: PunctuationConfig {
-                        enabled: true,
-                        standardize_dashes: true,
-                        standardize_ellipsis: true,
+                        enabled: Some(true),
+                        standardize_dashes: Some(StrategyOption::Enabled(true)),
+                        standardize_ellipsis: Some(true),
+                    },
+                    math: MathConfig {
+                        enabled: Some(true),
+                        delimiters: None,
+                    },
+                    emoji: EmojiConfig {
+                        enabled: Some(true),
+                        strip_headings: Some(true),
+                        strip_bullets: Some(true),
+                        body_text: Some("keep".to_string()),
+                    },
+                    invisible_characters: InvisibleCharactersConfig {
+                        enabled: Some(true),
+                        spaces: Some(true),
+                        hyphens: Some(true),
+                        zero_width: Some(true),
+                        soft_hyphens: Some(true),
+                        byte_order_marks: Some(true),
+                    },
+                    reasoning_blocks: ReasoningBlocksConfig {
+                        enabled: Some(true),
                     },
                 },
+                ..Default::default()
             }),
             files: None,
             vcs: None,
+            extends: None,
+            preset: None,
+            overrides: None,
         }
     }
 
@@ -479,7 +878,7 @@ mod tests {
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.smart_quotes.enabled = false;
+            markdown.rules.smart_quotes.enabled = Some(false);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -488,6 +887,26 @@ mod tests {
         assert_eq!(result, input);
     }
 
+    #[test]
+    fn test_smart_quotes_full_set() {
+        let mut formatter = MarkdownFormatter::new();
+        let mut config = create_test_config();
+        formatter.apply_configuration(&config).unwrap();
+
+        let input = "„Ja“ «oui» ＂wide＂ heʼs 5′10″ `„code“ 5′`";
+        let expected = "\"Ja\" \"oui\" \"wide\" he's 5'10\" `„code“ 5′`";
+        let result = formatter.format_content(input).unwrap();
+        assert_eq!(result, expected);
+
+        if let Some(ref mut markdown) = config.markdown {
+            markdown.rules.smart_quotes.guillemets = Some(false);
+            markdown.rules.smart_quotes.primes = Some(false);
+        }
+        formatter.apply_configuration(&config).unwrap();
+        let result = formatter.format_content("«oui» 5′10″").unwrap();
+        assert_eq!(result, "«oui» 5′10″");
+    }
+
     // Heading Tests
     #[test]
     fn test_headings_all_levels() {
@@ -531,7 +950,7 @@ mod tests {
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.headings.enabled = false;
+            markdown.rules.headings.enabled = Some(false);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -577,7 +996,7 @@ mod tests {
         ];
 
         for input in inputs.iter() {
-            let result = formatter.format_content(*input).unwrap();
+            let result = formatter.format_content(input).unwrap();
             assert_eq!(result, *input);
         }
     }
@@ -608,6 +1027,21 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_frontmatter_needs_yaml_keys() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "---\nripari:\n  headings: false\n---\nText";
+        assert_eq!(formatter.format_content(input).unwrap(), input);
+
+        let input = "---\nNot a key\n---\nText";
+        let expected = "\nNot a key\n\nText";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
     // Punctuation Tests
     #[test]
     fn test_punctuation_dashes_in_context() {
@@ -617,7 +1051,7 @@ mod tests {
             .unwrap();
 
         let input = "Word—word and word–word";
-        let expected = "Word-word and word-word";
+        let expected = "Word - word and word - word";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -643,7 +1077,7 @@ mod tests {
             .unwrap();
 
         let input = "Start—middle…end–final";
-        let expected = "Start-middle...end-final";
+        let expected = "Start - middle...end - final";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -653,7 +1087,7 @@ mod tests {
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.punctuation.enabled = false;
+            markdown.rules.punctuation.enabled = Some(false);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -698,10 +1132,10 @@ mod tests {
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.smart_quotes.enabled = false;
-            markdown.rules.headings.enabled = false;
-            markdown.rules.remove_horizontal_rules.enabled = false;
-            markdown.rules.punctuation.enabled = false;
+            markdown.rules.smart_quotes.enabled = Some(false);
+            markdown.rules.headings.enabled = Some(false);
+            markdown.rules.remove_horizontal_rules.enabled = Some(false);
+            markdown.rules.punctuation.enabled = Some(false);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -738,7 +1172,7 @@ Word—word…end"#;
             .unwrap();
 
         let input = r#"# **The "Quick"—Brown—Fox**"#;
-        let expected = r#"# The "Quick"-Brown-Fox"#;
+        let expected = r#"# The "Quick" - Brown - Fox"#;
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -771,11 +1205,11 @@ Here's a line with "quotes" and a dash—plus an ellipsis…
 
 ## **Another "Heading"—With—Style…**"#;
 
-        let expected = r#"# Don't "Quote" Me-I'm...
+        let expected = r#"# Don't "Quote" Me - I'm...
 
-Here's a line with "quotes" and a dash-plus an ellipsis...
+Here's a line with "quotes" and a dash - plus an ellipsis...
 
-## Another "Heading"-With-Style..."#;
+## Another "Heading" - With - Style..."#;
 
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
@@ -796,9 +1230,9 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
         ];
 
         let expected = [
-            r#"# "Quoted" Title-With-Dash..."#,
-            r#"# ...Dash-With-"Quoted" Title"#,
-            r#"# -"Quoted"...Title-"#,
+            r#"# "Quoted" Title - With - Dash..."#,
+            r#"# ...Dash - With - "Quoted" Title"#,
+            r#"# - "Quoted"...Title -"#,
         ];
 
         for (input, expected) in inputs.iter().zip(expected.iter()) {
@@ -813,12 +1247,12 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
             // Only disable smart quotes, keep other rules enabled
-            markdown.rules.smart_quotes.enabled = false;
+            markdown.rules.smart_quotes.enabled = Some(false);
         }
         formatter.apply_configuration(&config).unwrap();
 
         let input = r#"# **"Title" with—dash…**"#;
-        let expected = r#"# "Title" with-dash..."#;
+        let expected = r#"# "Title" with - dash..."#;
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -838,6 +1272,22 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_commonmark_fences() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "````md\n```\n“a” — b\n```\n````\n~~~\n---\n~~~\nc — d";
+        let expected = "````md\n```\n“a” — b\n```\n````\n~~~\n---\n~~~\nc - d";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+
+        let input = "a — b\n\n```\nc — d\n\n---\n“e”";
+        let expected = "a - b\n\n```\nc — d\n\n---\n“e”";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
     #[test]
     fn test_horizontal_rules_in_blockquotes() {
         let mut formatter = MarkdownFormatter::new();
@@ -846,9 +1296,18 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
             .unwrap();
 
         let input = "Before\n> ---\nAfter";
-        let expected = "Before\n> ---\nAfter";
+        let expected = "Before\n\nAfter";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
+
+        let input = "> Quoted\n> ---\n> ---\n> More\n>\n> ---\n> > Nested\n> > ---\n> > End";
+        let expected = "> Quoted\n>\n> More\n>\n> > Nested\n> >\n> > End";
+        let result = formatter.format_content(input).unwrap();
+        assert_eq!(result, expected);
+
+        let input = "> Quoted\n> ```\n> ---\n> ```\n> - ---";
+        let result = formatter.format_content(input).unwrap();
+        assert_eq!(result, input);
     }
 
     #[test]
@@ -917,12 +1376,12 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
 ## **Final "Heading"—With—Style…**"#;
 
         let expected = r#"# "Title"
-## Heading-With-Style...
+## Heading - With - Style...
 ## **Final "Heading"**"
 
-> Some "quoted" text with—dashes…
+> Some "quoted" text with - dashes...
 
-## Final "Heading"-With-Style..."#;
+## Final "Heading" - With - Style..."#;
 
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected, "Failed to format content correctly.\n\nActual input:\n{}\n\nActual output:\n{}\n\nActual expected:\n{}", input, result, expected);
@@ -933,10 +1392,10 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.smart_quotes.enabled = false;
-            markdown.rules.headings.enabled = true;
-            markdown.rules.remove_horizontal_rules.enabled = false;
-            markdown.rules.punctuation.enabled = false;
+            markdown.rules.smart_quotes.enabled = Some(false);
+            markdown.rules.headings.enabled = Some(true);
+            markdown.rules.remove_horizontal_rules.enabled = Some(false);
+            markdown.rules.punctuation.enabled = Some(false);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -986,7 +1445,33 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
             .unwrap();
 
         let input = "Text—with—multiple—dashes";
-        let expected = "Text-with-multiple-dashes";
+        let expected = "Text - with - multiple - dashes";
+        let result = formatter.format_content(input).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_dash_strategy() {
+        let mut formatter = MarkdownFormatter::new();
+        let mut config = create_test_config();
+        if let Some(ref mut markdown) = config.markdown {
+            // `true` never joins the words around a dash
+            markdown.rules.punctuation.standardize_dashes = Some(StrategyOption::Enabled(true));
+        }
+        formatter.apply_configuration(&config).unwrap();
+
+        let input = "# Plan—Draft\n\nIt works—mostly — `a—b`, see—`c`—then\n— Ada";
+        let expected = "# Plan - Draft\n\nIt works - mostly - `a—b`, see - `c` - then\n\\- Ada";
+        let result = formatter.format_content(input).unwrap();
+        assert_eq!(result, expected);
+
+        if let Some(ref mut markdown) = config.markdown {
+            markdown.rules.punctuation.standardize_dashes =
+                Some(StrategyOption::Named("comma".to_string()));
+        }
+        formatter.apply_configuration(&config).unwrap();
+        let input = "— Ada\n\nsee—`c` or wait—";
+        let expected = "Ada\n\nsee, `c` or wait";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -1012,7 +1497,7 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
             .unwrap();
 
         let input = "Text—with…mixed—punctuation…marks";
-        let expected = "Text-with...mixed-punctuation...marks";
+        let expected = "Text - with...mixed - punctuation...marks";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -1025,7 +1510,7 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
             .unwrap();
 
         let input = "Text——with……consecutive—punctuation";
-        let expected = "Text--with......consecutive-punctuation";
+        let expected = "Text - with......consecutive - punctuation";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -1085,7 +1570,7 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
             .unwrap();
 
         let input = "## Heading—With—Dashes";
-        let expected = "## Heading-With-Dashes";
+        let expected = "## Heading - With - Dashes";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -1139,18 +1624,220 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
     }
 
     #[test]
-    fn test_blockquote_preservation() {
+    fn test_blockquote_formatting() {
         let mut formatter = MarkdownFormatter::new();
         formatter
             .apply_configuration(&create_test_config())
             .unwrap();
 
-        let input = "> Text with—dashes… and \"quotes\"";
-        let expected = "> Text with—dashes… and \"quotes\"";
+        let input = "> Text with—dashes… and “quotes”";
+        let expected = "> Text with - dashes... and \"quotes\"";
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_inline_code_is_preserved() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "Run `a—b` or ``“x”…`` — then “go”";
+        let expected = "Run `a—b` or ``“x”…`` - then \"go\"";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_links_and_urls_are_preserved() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "See [the “guide”…](https://a.com/x…y) and <https://b.com/a—b>";
+        let expected = "See [the \"guide\"...](https://a.com/x…y) and <https://b.com/a—b>";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+
+        let input = "Visit https://a.com/a—b… — today\n\n[ref]: https://c.com/… “Title”";
+        let expected = "Visit https://a.com/a—b… - today\n\n[ref]: https://c.com/… “Title”";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_raw_html_is_preserved() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "<abbr title=“Ellipsis…”>E—</abbr> and <!-- a—b -->";
+        let expected = "<abbr title=“Ellipsis…”>E - </abbr> and <!-- a—b -->";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_math_is_preserved() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "Take $a–b$ — or $$x…y$$\n\n$$\nf(x) = ‘x’ – 1\n$$\n\nIt costs $5 — or $10";
+        let expected = "Take $a–b$ - or $$x…y$$\n\n$$\nf(x) = ‘x’ – 1\n$$\n\nIt costs $5 - or $10";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    fn math_config(
+        enabled: bool,
+        delimiters: Option<&str>,
+    ) -> PartialMarkdownFormatterConfiguration {
+        let mut config = create_test_config();
+        config.markdown.as_mut().unwrap().rules.math = MathConfig {
+            enabled: Some(enabled),
+            delimiters: delimiters.map(str::to_string),
+        };
+        config
+    }
+
+    #[test]
+    fn test_math_delimiter_conversion() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&math_config(true, Some("dollars")))
+            .unwrap();
+        let input = "So \\( a – b \\) — and\n\n> \\[\n> x…y\n> \\]";
+        let expected = "So $a – b$ - and\n\n> $$\n> x…y\n> $$";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+
+        formatter
+            .apply_configuration(&math_config(true, Some("brackets")))
+            .unwrap();
+        let input = "So $a–b$ and $$x$$\n\n$$\ny\n$$";
+        let expected = "So \\(a–b\\) and \\[x\\]\n\n\\[\ny\n\\]";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_math_disabled() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&math_config(false, Some("dollars")))
+            .unwrap();
+        let input = "Take $a–b$ and \\(c–d\\)\n\n$$\n‘x’\n$$";
+        let expected = "Take $a - b$ and \\(c - d\\)\n\n$$\n'x'\n$$";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_emoji_in_headings_and_bullets() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "### 🎯 **Fractional CTO**\n\n✔ Proven success 🚀\n- 🌐 `🌐` Advisory\n\n# 🎉";
+        let expected = "### Fractional CTO\n\nProven success 🚀\n- `🌐` Advisory\n\n# 🎉";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+
+        // The rule is left to the presets of assistants writing emoji
+        let defaults = PartialMarkdownFormatterConfiguration {
+            markdown: Some(MarkdownFormatterConfig::default()),
+            ..Default::default()
+        };
+        formatter.apply_configuration(&defaults).unwrap();
+        assert_eq!(
+            formatter.format_content("### 🎯 Goals").unwrap(),
+            "### 🎯 Goals"
+        );
+    }
+
+    #[test]
+    fn test_invisible_characters() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "\u{FEFF}10\u{202F}km\u{200B}\n\n```\na\u{00A0}b\n```\n\
+                     <!-- ripari-disable-next-line invisible_characters -->\n\
+                     e\u{2011}mail";
+        let expected = "10 km\n\n```\na\u{00A0}b\n```\n\
+                        <!-- ripari-disable-next-line invisible_characters -->\n\
+                        e\u{2011}mail";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+        let found = formatter.invisible_characters(input);
+        let columns: Vec<(usize, usize)> = found.iter().map(|c| (c.line, c.column)).collect();
+        assert_eq!(columns, [(1, 1), (1, 4), (1, 7)]);
+
+        // Code spans and math are left as written, and not reported
+        let input = "> a\u{00A0}b `c\u{00A0}d` $e\u{2009}f$\n$$\ng\u{2009}h\n$$";
+        let expected = "> a b `c\u{00A0}d` $e\u{2009}f$\n$$\ng\u{2009}h\n$$";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+        let found = formatter.invisible_characters(input);
+        let columns: Vec<(usize, usize)> = found.iter().map(|c| (c.line, c.column)).collect();
+        assert_eq!(columns, [(1, 4)]);
+
+        // Off by default, the rule neither changes nor reports anything
+        let defaults = PartialMarkdownFormatterConfiguration {
+            markdown: Some(MarkdownFormatterConfig::default()),
+            ..Default::default()
+        };
+        formatter.apply_configuration(&defaults).unwrap();
+        assert_eq!(
+            formatter.format_content("5\u{00A0}km").unwrap(),
+            "5\u{00A0}km"
+        );
+        assert!(formatter.invisible_characters("5\u{00A0}km").is_empty());
+
+        let mut config = create_test_config();
+        config.markdown.as_mut().unwrap().enabled = Some(false);
+        formatter.apply_configuration(&config).unwrap();
+        assert!(formatter.invisible_characters("5\u{00A0}km").is_empty());
+    }
+
+    #[test]
+    fn test_emoji_in_body_text() {
+        let mut config = create_test_config();
+        let emoji = &mut config.markdown.as_mut().unwrap().rules.emoji;
+        emoji.strip_bullets = Some(false);
+        emoji.body_text = Some("shortcode".to_string());
+        let mut formatter = MarkdownFormatter::new();
+        formatter.apply_configuration(&config).unwrap();
+
+        let input = "🌟 **Open for work** 🌟\n\nShip it 👍🏽, see `🚀`";
+        let expected = ":star2: **Open for work** :star2:\n\nShip it :+1::skin-tone-4:, see `🚀`";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+
+        config.markdown.as_mut().unwrap().rules.emoji.body_text = Some("remove".to_string());
+        formatter.apply_configuration(&config).unwrap();
+        let expected = "**Open for work**\n\nShip it, see `🚀`";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_nested_containers_formatting() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "> [!NOTE]\n> ## **Heads up**\n> > It’s “nested”\n\n- item — one\n  2. **bold** … “two”\n> - `a—b` — c";
+        let expected = "> [!NOTE]\n> ## Heads up\n> > It's \"nested\"\n\n- item - one\n  2. **bold** ... \"two\"\n> - `a—b` - c";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_code_blocks_in_containers_are_preserved() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "> ```\n> a — “b”\n> ```\n- ```\n  c — d\n  ```";
+        assert_eq!(formatter.format_content(input).unwrap(), input);
+    }
+
     #[test]
     fn test_horizontal_rule_removal() {
         let mut formatter = MarkdownFormatter::new();
@@ -1169,7 +1856,10 @@ Here's a line with "quotes" and a dash-plus an ellipsis...
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1202,8 +1892,11 @@ date: 2025-01-06
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = false;
-            markdown.rules.remove_horizontal_rules.enabled = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(false);
+            markdown.rules.remove_horizontal_rules.enabled = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1235,7 +1928,10 @@ date: 2025-01-06
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1300,7 +1996,10 @@ date: 2025-01-06
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1324,7 +2023,10 @@ date: 2025-01-06
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1358,7 +2060,10 @@ categories: [Test]
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1384,7 +2089,10 @@ date: 2025-01-06
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1422,7 +2130,10 @@ second block of text
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1458,7 +2169,10 @@ metadata:
         let mut formatter = MarkdownFormatter::new();
         let mut config = create_test_config();
         if let Some(ref mut markdown) = config.markdown {
-            markdown.rules.remove_horizontal_rules.retain_frontmatter_wrappers = true;
+            markdown
+                .rules
+                .remove_horizontal_rules
+                .retain_frontmatter_wrappers = Some(true);
         }
         formatter.apply_configuration(&config).unwrap();
 
@@ -1494,4 +2208,85 @@ fn main() {
         let result = formatter.format_content(input).unwrap();
         assert_eq!(result, expected);
     }
+
+    // Suppression Comments Tests
+    #[test]
+    fn test_suppression_comments() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "<!-- ripari-disable punctuation -->\n\
+                     An em dash — kept, “quotes” fixed\n\
+                     <!-- ripari-enable -->\n\
+                     An em dash — fixed\n\
+                     <!-- ripari-disable-next-line remove_horizontal_rules -->\n\
+                     ---\n\
+                     Text\n\
+                     ---\n\
+                     End";
+        let expected = "<!-- ripari-disable punctuation -->\n\
+                        An em dash — kept, \"quotes\" fixed\n\
+                        <!-- ripari-enable -->\n\
+                        An em dash - fixed\n\
+                        <!-- ripari-disable-next-line remove_horizontal_rules -->\n\
+                        ---\n\
+                        Text\n\
+                        \n\
+                        End";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+
+        let input = "# **Style**\n<!-- ripari-disable-file headings -->\n";
+        assert_eq!(formatter.format_content(input).unwrap(), input);
+    }
+
+    #[test]
+    fn test_unused_suppressions() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "<!-- ripari-disable-next-line smart_quotes -->\n\
+                     An em dash —\n\
+                     <!-- ripari-disable punctuation -->\n\
+                     An em dash —\n\
+                     <!-- ripari-enable -->\n\
+                     ```\n\
+                     <!-- ripari-disable -->\n\
+                     ```\n";
+        let unused = formatter.unused_suppressions(input).unwrap();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].line, 1);
+        assert_eq!(
+            unused[0].to_string(),
+            "ripari-disable-next-line smart_quotes"
+        );
+    }
+
+    #[test]
+    fn test_reasoning_blocks() {
+        let mut formatter = MarkdownFormatter::new();
+        formatter
+            .apply_configuration(&create_test_config())
+            .unwrap();
+
+        let input = "<think>\nThe user wants “quotes”.\n</think>\n\n# **Answer**\n\n\
+                     <!-- ripari-disable-next-line reasoning_blocks -->\n\
+                     <think>kept</think>\n";
+        let expected = "# Answer\n\n\
+                        <!-- ripari-disable-next-line reasoning_blocks -->\n\
+                        <think>kept</think>\n";
+        assert_eq!(formatter.format_content(input).unwrap(), expected);
+        assert!(formatter.unused_suppressions(input).unwrap().is_empty());
+
+        // Comments removed with a block do not take the place of those after it
+        let input = "<think>\n<!-- ripari-disable-next-line smart_quotes -->\nplan\n</think>\n\n\
+                     <!-- ripari-disable-next-line punctuation -->\n\
+                     An em dash —\n";
+        let unused = formatter.unused_suppressions(input).unwrap();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].line, 2);
+    }
 }
@@ -0,0 +1,142 @@
+/// The delimiters the `math` rule rewrites math with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MathStyle {
+    /// `$...$` inline and `$$...$$` display math
+    Dollars,
+    /// `\(...\)` inline and `\[...\]` display math
+    Brackets,
+}
+
+impl MathStyle {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "dollars" => Some(MathStyle::Dollars),
+            "brackets" => Some(MathStyle::Brackets),
+            _ => None,
+        }
+    }
+
+    fn delimiters(self, display: bool) -> (&'static str, &'static str) {
+        match (self, display) {
+            (MathStyle::Dollars, false) => ("$", "$"),
+            (MathStyle::Dollars, true) => ("$$", "$$"),
+            (MathStyle::Brackets, false) => (r"\(", r"\)"),
+            (MathStyle::Brackets, true) => (r"\[", r"\]"),
+        }
+    }
+}
+
+/// Delimiters of inline and display math, longest first
+const DELIMITERS: &[(&str, &str, bool)] = &[
+    ("$$", "$$", true),
+    (r"\[", r"\]", true),
+    (r"\(", r"\)", false),
+    ("$", "$", false),
+];
+
+/// Rewrites `math`, a math span with its delimiters, with the delimiters of `style`
+pub(crate) fn convert(math: &str, style: MathStyle) -> String {
+    let Some((display, body)) = DELIMITERS.iter().find_map(|(open, close, display)| {
+        let body = math.strip_prefix(open)?.strip_suffix(close)?;
+        Some((*display, body))
+    }) else {
+        return math.to_string();
+    };
+    // Inline `$` math may not start or end with a space
+    let body = match (style, display) {
+        (MathStyle::Dollars, false) => body.trim(),
+        _ => body,
+    };
+    if body.is_empty() {
+        return math.to_string();
+    }
+    let (open, close) = style.delimiters(display);
+    format!("{}{}{}", open, body, close)
+}
+
+/// Where a line stands relative to display math written over several lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MathLine {
+    Outside,
+    Opening,
+    Inside,
+    Closing,
+}
+
+/// Follows display math blocks line by line: a `$$` line opens a block closed by the next
+/// `$$` line, and a `\[` line one closed by a `\]` line
+#[derive(Debug, Default)]
+pub(crate) struct MathBlocks {
+    closing: Option<&'static str>,
+}
+
+impl MathBlocks {
+    /// Classifies `content`, the text of a line without its container markers
+    pub fn classify(&mut self, content: &str) -> MathLine {
+        let delimiter = content.trim();
+        match self.closing {
+            Some(closing) if delimiter == closing => {
+                self.closing = None;
+                MathLine::Closing
+            }
+            Some(_) => MathLine::Inside,
+            None => {
+                self.closing = match delimiter {
+                    "$$" => Some("$$"),
+                    r"\[" => Some(r"\]"),
+                    _ => return MathLine::Outside,
+                };
+                MathLine::Opening
+            }
+        }
+    }
+}
+
+/// Rewrites the delimiter opening or closing a display math block, keeping its indentation
+pub(crate) fn convert_delimiter_line(content: &str, line: MathLine, style: MathStyle) -> String {
+    let (open, close) = style.delimiters(true);
+    let delimiter = if line == MathLine::Opening {
+        open
+    } else {
+        close
+    };
+    content.replacen(content.trim(), delimiter, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(convert(r"\( x^2 \)", MathStyle::Dollars), "$x^2$");
+        assert_eq!(convert(r"\[a - b\]", MathStyle::Dollars), "$$a - b$$");
+        assert_eq!(convert("$x$", MathStyle::Brackets), r"\(x\)");
+        assert_eq!(convert("$$x$$", MathStyle::Brackets), r"\[x\]");
+        assert_eq!(convert("$x$", MathStyle::Dollars), "$x$");
+        assert_eq!(convert(r"\( \)", MathStyle::Dollars), r"\( \)");
+    }
+
+    #[test]
+    fn test_display_math_blocks() {
+        let mut blocks = MathBlocks::default();
+        let lines: Vec<MathLine> = [r"\[", "$$", r"\]", "$$", "$$"]
+            .iter()
+            .map(|line| blocks.classify(line))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                MathLine::Opening,
+                MathLine::Inside,
+                MathLine::Closing,
+                MathLine::Opening,
+                MathLine::Closing
+            ]
+        );
+        assert_eq!(
+            convert_delimiter_line("  \\]", MathLine::Closing, MathStyle::Dollars),
+            "  $$"
+        );
+    }
+}
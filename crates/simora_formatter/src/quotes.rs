@@ -0,0 +1,100 @@
+use simora_configuration::SmartQuotesConfig;
+
+/// The classes of quotes handled by the `smart_quotes` rule, curly quotes being always
+/// replaced and the others each having their own option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Curly,
+    LowQuote,
+    Guillemet,
+    ModifierApostrophe,
+    FullWidth,
+    Prime,
+}
+
+impl Class {
+    fn is_enabled(self, config: &SmartQuotesConfig) -> bool {
+        let option = match self {
+            Class::Curly => return true,
+            Class::LowQuote => config.low_quotes,
+            Class::Guillemet => config.guillemets,
+            Class::ModifierApostrophe => config.modifier_apostrophes,
+            Class::FullWidth => config.full_width,
+            Class::Prime => config.primes,
+        };
+        option.unwrap_or_default()
+    }
+}
+
+/// Every quote the rule replaces, with its ASCII replacement
+const QUOTES: &[(char, char, Class)] = &[
+    ('\u{201C}', '"', Class::Curly),
+    ('\u{201D}', '"', Class::Curly),
+    ('\u{201F}', '"', Class::Curly),
+    ('\u{2018}', '\'', Class::Curly),
+    ('\u{2019}', '\'', Class::Curly),
+    ('\u{201B}', '\'', Class::Curly),
+    ('\u{201E}', '"', Class::LowQuote),
+    ('\u{201A}', '\'', Class::LowQuote),
+    ('\u{00AB}', '"', Class::Guillemet),
+    ('\u{00BB}', '"', Class::Guillemet),
+    ('\u{2039}', '\'', Class::Guillemet),
+    ('\u{203A}', '\'', Class::Guillemet),
+    ('\u{02BC}', '\'', Class::ModifierApostrophe),
+    ('\u{FF02}', '"', Class::FullWidth),
+    ('\u{FF07}', '\'', Class::FullWidth),
+    ('\u{2032}', '\'', Class::Prime),
+    ('\u{2033}', '"', Class::Prime),
+];
+
+/// Replaces the quotes of `text` enabled in `config` with straight ASCII quotes
+pub(crate) fn straighten(text: &str, config: &SmartQuotesConfig) -> String {
+    text.chars()
+        .map(|c| {
+            QUOTES
+                .iter()
+                .find(|(quote, _, class)| *quote == c && class.is_enabled(config))
+                .map_or(c, |(_, replacement, _)| *replacement)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_classes() -> SmartQuotesConfig {
+        SmartQuotesConfig {
+            enabled: Some(true),
+            low_quotes: Some(true),
+            guillemets: Some(true),
+            modifier_apostrophes: Some(true),
+            full_width: Some(true),
+            primes: Some(true),
+        }
+    }
+
+    #[test]
+    fn test_straighten() {
+        let config = all_classes();
+        assert_eq!(
+            straighten("„Hallo“, ‚du‘ «oui» ‹non› it\u{02BC}s", &config),
+            "\"Hallo\", 'du' \"oui\" 'non' it's"
+        );
+        assert_eq!(straighten("＂full＇ 5′10″", &config), "\"full' 5'10\"");
+    }
+
+    #[test]
+    fn test_classes_are_optional() {
+        let config = SmartQuotesConfig {
+            enabled: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(straighten("“a” «b» 5′10″", &config), "\"a\" «b» 5′10″");
+        let config = SmartQuotesConfig {
+            primes: Some(false),
+            ..all_classes()
+        };
+        assert_eq!(straighten("‘a’ 5′10″", &config), "'a' 5′10″");
+    }
+}
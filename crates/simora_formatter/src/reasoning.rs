@@ -0,0 +1,108 @@
+use crate::fences::Fences;
+
+/// Tags reasoning models wrap their chain of thought in
+const TAGS: &[&str] = &["think", "thinking"];
+
+/// The tag of the reasoning block `line` opens, if it starts with one
+fn opening_tag(line: &str) -> Option<&'static str> {
+    let line = line.trim_start();
+    TAGS.iter()
+        .find(|tag| {
+            line.strip_prefix('<')
+                .and_then(|rest| rest.strip_prefix(**tag))
+                .is_some_and(|rest| rest.starts_with('>'))
+        })
+        .copied()
+}
+
+/// Removes the `<think>` blocks of `content` outside of code blocks, along with the blank
+/// lines following them. Text after a closing tag is kept, and blocks never closed or on
+/// lines for which `suppressed` is true are left as written. Also returns the line of
+/// `content` each line of the result comes from.
+pub(crate) fn remove_reasoning_blocks(
+    content: &str,
+    mut suppressed: impl FnMut(usize) -> bool,
+) -> (String, Vec<usize>) {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut fences = Fences::default();
+    let mut result = String::new();
+    let mut origins = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let text = line.trim_end_matches(['\r', '\n']);
+        let tag = match fences.classify(text).is_code() {
+            true => None,
+            false => opening_tag(text),
+        };
+        let closing = tag.map(|tag| format!("</{}>", tag));
+        let end = closing.as_ref().and_then(|closing| {
+            (index..lines.len()).find(|&end| lines[end].contains(closing.as_str()))
+        });
+        let (Some(closing), Some(end)) = (closing, end) else {
+            result.push_str(line);
+            origins.push(index);
+            index += 1;
+            continue;
+        };
+        if suppressed(index) {
+            result.push_str(line);
+            origins.push(index);
+            index += 1;
+            continue;
+        }
+
+        let last = lines[end];
+        let rest = &last[last.find(&closing).unwrap_or_default() + closing.len()..];
+        if !rest.trim().is_empty() {
+            result.push_str(rest.trim_start_matches([' ', '\t']));
+            origins.push(end);
+        }
+        index = end + 1;
+        while index < lines.len() && lines[index].trim().is_empty() {
+            index += 1;
+        }
+    }
+    (result, origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remove(content: &str) -> String {
+        remove_reasoning_blocks(content, |_| false).0
+    }
+
+    #[test]
+    fn test_remove_reasoning_blocks() {
+        assert_eq!(
+            remove("<think>\nThe user wants a list.\n</think>\n\n# Answer\n"),
+            "# Answer\n"
+        );
+        assert_eq!(
+            remove("Intro\n\n<thinking>Plan</thinking> Then text\nMore\n"),
+            "Intro\n\nThen text\nMore\n"
+        );
+        assert_eq!(remove("<think>\r\nhm\r\n</think>\r\nA\r\n"), "A\r\n");
+    }
+
+    #[test]
+    fn test_origins() {
+        let (_, origins) = remove_reasoning_blocks("a\n<think>\nb\n</think> c\n\nd\n", |_| false);
+        assert_eq!(origins, [0, 3, 5]);
+    }
+
+    #[test]
+    fn test_keeps_code_and_unclosed_blocks() {
+        let code = "```html\n<think>\n</think>\n```\n";
+        assert_eq!(remove(code), code);
+        let unclosed = "<think>\nstill going\n";
+        assert_eq!(remove(unclosed), unclosed);
+        assert_eq!(remove("<thinker>x</thinker>\n"), "<thinker>x</thinker>\n");
+        assert_eq!(
+            remove_reasoning_blocks("<think>a</think>\n", |line| line == 0).0,
+            "<think>a</think>\n"
+        );
+    }
+}
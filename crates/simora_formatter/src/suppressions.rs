@@ -0,0 +1,219 @@
+use crate::fences::Fences;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+
+const DIRECTIVE_PATTERN: &str =
+    r"^\s*<!--\s*ripari-(disable-next-line|disable-file|disable|enable)\b(.*?)-->\s*$";
+
+/// What a suppression comment does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    /// `ripari-disable`, until the next `ripari-enable`
+    Disable,
+    /// `ripari-enable`, ending a `ripari-disable`
+    Enable,
+    /// `ripari-disable-next-line`
+    DisableNextLine,
+    /// `ripari-disable-file`, anywhere in the file
+    DisableFile,
+}
+
+/// A suppression comment such as `<!-- ripari-disable smart_quotes -->`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    /// 1-based line of the comment
+    pub line: usize,
+    pub kind: DirectiveKind,
+    /// The rules named, all rules when empty
+    pub rules: Vec<String>,
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.kind {
+            DirectiveKind::Disable => "ripari-disable",
+            DirectiveKind::Enable => "ripari-enable",
+            DirectiveKind::DisableNextLine => "ripari-disable-next-line",
+            DirectiveKind::DisableFile => "ripari-disable-file",
+        };
+        match self.rules.is_empty() {
+            true => write!(f, "{}", name),
+            false => write!(f, "{} {}", name, self.rules.join(" ")),
+        }
+    }
+}
+
+/// The rules disabled on a line, each mapped to the directive that disabled it.
+/// `*` stands for all rules, and `None` re-enables a rule disabled through `*`.
+type Scope = BTreeMap<String, Option<usize>>;
+
+const ALL_RULES: &str = "*";
+
+/// The suppression comments of a document and the rules they disable on each line
+#[derive(Debug, Default)]
+pub(crate) struct Suppressions {
+    pub directives: Vec<Directive>,
+    file: Scope,
+    lines: Vec<Scope>,
+    /// The directives that kept a rule from changing something
+    used: Vec<bool>,
+}
+
+impl Suppressions {
+    pub fn parse(content: &str) -> Self {
+        let mut suppressions = Self::default();
+        let mut current = Scope::new();
+        let mut next_line: Option<Scope> = None;
+        let pattern = Regex::new(DIRECTIVE_PATTERN).unwrap();
+        let mut fences = Fences::default();
+        for (index, line) in content.split('\n').enumerate() {
+            let scope = next_line.take().unwrap_or_else(|| current.clone());
+            suppressions.lines.push(scope);
+
+            // Comments in code blocks are shown, not obeyed
+            if fences.classify(line).is_code() {
+                continue;
+            }
+            let Some(directive) = parse_directive(&pattern, line, index + 1) else {
+                continue;
+            };
+            let id = suppressions.directives.len();
+            match directive.kind {
+                DirectiveKind::Disable => disable(&mut current, &directive.rules, id),
+                DirectiveKind::Enable if directive.rules.is_empty() => current.clear(),
+                DirectiveKind::Enable => {
+                    for rule in &directive.rules {
+                        match current.contains_key(ALL_RULES) {
+                            true => current.insert(rule.clone(), None),
+                            false => current.remove(rule),
+                        };
+                    }
+                }
+                DirectiveKind::DisableNextLine => {
+                    let mut scope = current.clone();
+                    disable(&mut scope, &directive.rules, id);
+                    next_line = Some(scope);
+                }
+                DirectiveKind::DisableFile => disable(&mut suppressions.file, &directive.rules, id),
+            }
+            suppressions.directives.push(directive);
+        }
+        suppressions.used = vec![false; suppressions.directives.len()];
+        suppressions
+    }
+
+    /// The directive disabling `rule` on the 0-based `line`, if any
+    fn disabled_by(&self, line: usize, rule: &str) -> Option<usize> {
+        let lookup = |scope: &Scope| match scope.get(rule) {
+            Some(id) => *id,
+            None => scope.get(ALL_RULES).copied().flatten(),
+        };
+        self.lines
+            .get(line)
+            .and_then(lookup)
+            .or_else(|| lookup(&self.file))
+    }
+
+    /// Whether `rule` is disabled on `line`, recording the directive as used if the rule
+    /// would have changed it
+    pub fn suppresses(&mut self, line: usize, rule: &str, would_change: bool) -> bool {
+        let Some(id) = self.disabled_by(line, rule) else {
+            return false;
+        };
+        if would_change {
+            self.used[id] = true;
+        }
+        true
+    }
+
+    /// `formatted` unless `rule` is disabled on `line`, in which case `original`
+    pub fn apply(&mut self, line: usize, rule: &str, original: &str, formatted: String) -> String {
+        match self.suppresses(line, rule, formatted != original) {
+            true => original.to_string(),
+            false => formatted,
+        }
+    }
+
+    pub fn used(&self) -> &[bool] {
+        &self.used
+    }
+}
+
+fn disable(scope: &mut Scope, rules: &[String], id: usize) {
+    if rules.is_empty() {
+        scope.clear();
+        scope.insert(ALL_RULES.to_string(), Some(id));
+    }
+    for rule in rules {
+        scope.insert(rule.clone(), Some(id));
+    }
+}
+
+fn parse_directive(pattern: &Regex, line: &str, number: usize) -> Option<Directive> {
+    let captures = pattern.captures(line)?;
+    let kind = match &captures[1] {
+        "disable" => DirectiveKind::Disable,
+        "enable" => DirectiveKind::Enable,
+        "disable-next-line" => DirectiveKind::DisableNextLine,
+        _ => DirectiveKind::DisableFile,
+    };
+    let rules = captures[2]
+        .split([',', ' ', '\t'])
+        .filter(|rule| !rule.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some(Directive {
+        line: number,
+        kind,
+        rules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directives() {
+        let pattern = Regex::new(DIRECTIVE_PATTERN).unwrap();
+        let parse_directive = |line, number| parse_directive(&pattern, line, number);
+        let directive = parse_directive("<!-- ripari-disable smart_quotes, punctuation -->", 3);
+        assert_eq!(
+            directive,
+            Some(Directive {
+                line: 3,
+                kind: DirectiveKind::Disable,
+                rules: vec!["smart_quotes".to_string(), "punctuation".to_string()],
+            })
+        );
+        let directive = parse_directive("<!--ripari-disable-next-line-->", 1).unwrap();
+        assert_eq!(directive.kind, DirectiveKind::DisableNextLine);
+        assert!(directive.rules.is_empty());
+        assert_eq!(parse_directive("<!-- ripari-disabled -->", 1), None);
+        assert_eq!(parse_directive("text <!-- ripari-disable -->", 1), None);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let content = "a\n\
+                       <!-- ripari-disable -->\n\
+                       b\n\
+                       <!-- ripari-enable punctuation -->\n\
+                       c\n\
+                       <!-- ripari-enable -->\n\
+                       <!-- ripari-disable-next-line headings -->\n\
+                       d\n\
+                       e\n\
+                       <!-- ripari-disable-file smart_quotes -->";
+        let suppressions = Suppressions::parse(content);
+        let disabled = |line: usize, rule: &str| suppressions.disabled_by(line, rule).is_some();
+        assert!(!disabled(0, "punctuation"));
+        assert!(disabled(2, "punctuation"));
+        assert!(!disabled(4, "punctuation"));
+        assert!(disabled(4, "headings"));
+        assert!(disabled(7, "headings"));
+        assert!(!disabled(8, "headings"));
+        assert!(disabled(0, "smart_quotes"));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::fences::{FenceLine, Fences};
+use simora_configuration::MarkdownFormatterConfig;
+
+/// CommonMark expands tabs to the next multiple of four columns
+const TAB_STOP: usize = 4;
+
+/// Applies `end_of_line`, `trim_trailing_whitespace`, `indent_style` and
+/// `insert_final_newline`. Code blocks only get their line endings changed.
+pub(crate) fn normalize_whitespace(content: &str, config: &MarkdownFormatterConfig) -> String {
+    let end_of_line = match config.end_of_line.as_deref() {
+        Some("crlf") => Some("\r\n"),
+        Some("cr") => Some("\r"),
+        Some("lf") => Some("\n"),
+        _ => None,
+    };
+    let trim = config.trim_trailing_whitespace.unwrap_or_default();
+    let indent: Option<fn(&str) -> String> = match config.indent_style.as_deref() {
+        Some("space") => Some(expand_leading_tabs),
+        Some("tab") => Some(collapse_leading_spaces),
+        _ => None,
+    };
+
+    let lines = split_lines(content);
+    let mut result = String::with_capacity(content.len());
+    let mut fences = Fences::default();
+    for (index, (line, ending)) in lines.iter().enumerate() {
+        if fences.classify(line) == FenceLine::Inside {
+            result.push_str(line);
+        } else {
+            let next_is_text = lines
+                .get(index + 1)
+                .is_some_and(|(next, _)| !next.trim().is_empty());
+            let line = if trim {
+                trim_line(line, next_is_text)
+            } else {
+                line.to_string()
+            };
+            match indent {
+                Some(indent) => result.push_str(&indent(&line)),
+                None => result.push_str(&line),
+            }
+        }
+        if !ending.is_empty() {
+            result.push_str(end_of_line.unwrap_or(ending));
+        }
+    }
+
+    match config.insert_final_newline {
+        Some(true) if !result.ends_with(['\n', '\r']) => {
+            let ending = end_of_line
+                .or_else(|| lines.first().map(|(_, ending)| *ending))
+                .filter(|ending| !ending.is_empty())
+                .unwrap_or("\n");
+            result.push_str(ending);
+        }
+        Some(false) => result.truncate(result.trim_end_matches(['\n', '\r']).len()),
+        _ => {}
+    }
+    result
+}
+
+/// Numbers of the lines longer than `max` characters, code blocks aside
+pub(crate) fn long_lines(content: &str, max: usize) -> Vec<usize> {
+    let mut fences = Fences::default();
+    let mut long = Vec::new();
+    for (index, (line, _)) in split_lines(content).iter().enumerate() {
+        if !fences.classify(line).is_code() && line.chars().count() > max {
+            long.push(index + 1);
+        }
+    }
+    long
+}
+
+/// Splits `content` into lines and the `\n`, `\r\n` or `\r` ending each one
+fn split_lines(content: &str) -> Vec<(&str, &str)> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let bytes = content.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let ending_length = match bytes[index] {
+            b'\r' if bytes.get(index + 1) == Some(&b'\n') => 2,
+            b'\r' | b'\n' => 1,
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+        let end = index + ending_length;
+        lines.push((&content[start..index], &content[index..end]));
+        start = end;
+        index = end;
+    }
+    if start < content.len() {
+        lines.push((&content[start..], ""));
+    }
+    lines
+}
+
+/// Two or more trailing spaces before more text are a hard line break, kept as two spaces.
+/// Headings cannot hold a hard break.
+fn trim_line(line: &str, next_is_text: bool) -> String {
+    let trimmed = line.trim_end();
+    let next_is_text = next_is_text && !trimmed.trim_start().starts_with('#');
+    let trailing_spaces = line[trimmed.len()..]
+        .chars()
+        .take_while(|c| *c == ' ')
+        .count();
+    if !trimmed.is_empty() && next_is_text && trailing_spaces >= 2 {
+        format!("{}  ", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// The width in columns of the leading spaces and tabs of `line`, and the rest of it
+fn split_indentation(line: &str) -> (usize, &str) {
+    let rest = line.trim_start_matches([' ', '\t']);
+    let width = line[..line.len() - rest.len()]
+        .chars()
+        .fold(0, |width, c| match c {
+            '\t' => width + TAB_STOP - width % TAB_STOP,
+            _ => width + 1,
+        });
+    (width, rest)
+}
+
+fn expand_leading_tabs(line: &str) -> String {
+    let (width, rest) = split_indentation(line);
+    " ".repeat(width) + rest
+}
+
+/// Each `TAB_STOP` columns of indentation become a tab, the columns left over spaces
+fn collapse_leading_spaces(line: &str) -> String {
+    let (width, rest) = split_indentation(line);
+    "\t".repeat(width / TAB_STOP) + &" ".repeat(width % TAB_STOP) + rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_of_line() {
+        let crlf = MarkdownFormatterConfig {
+            end_of_line: Some("crlf".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(normalize_whitespace("a\nb\r\nc", &crlf), "a\r\nb\r\nc");
+        let lf = MarkdownFormatterConfig {
+            end_of_line: Some("lf".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(normalize_whitespace("a\r\nb\rc\n", &lf), "a\nb\nc\n");
+        assert_eq!(
+            normalize_whitespace("a\r\nb\n", &MarkdownFormatterConfig::default()),
+            "a\r\nb\n"
+        );
+    }
+
+    #[test]
+    fn test_final_newline() {
+        let insert = MarkdownFormatterConfig {
+            insert_final_newline: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(normalize_whitespace("a\r\nb", &insert), "a\r\nb\r\n");
+        assert_eq!(normalize_whitespace("a\n", &insert), "a\n");
+        let remove = MarkdownFormatterConfig {
+            insert_final_newline: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(normalize_whitespace("a\n\n", &remove), "a");
+    }
+
+    #[test]
+    fn test_trim_keeps_hard_breaks_and_code() {
+        let trim = MarkdownFormatterConfig {
+            trim_trailing_whitespace: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_whitespace("# a  \nline   \nbreak \t\n\n```\ncode  \n```\n", &trim),
+            "# a\nline  \nbreak\n\n```\ncode  \n```\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_leading_tabs() {
+        let spaces = MarkdownFormatterConfig {
+            indent_style: Some("space".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_whitespace("\t- item\n  \tnested\ta\n", &spaces),
+            "    - item\n    nested\ta\n"
+        );
+    }
+
+    #[test]
+    fn test_collapse_leading_spaces() {
+        let tabs = MarkdownFormatterConfig {
+            indent_style: Some("tab".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_whitespace(
+                "- item\n    - nested\n      deeper  a\n  \tmixed\n```\n    code\n```\n",
+                &tabs
+            ),
+            "- item\n\t- nested\n\t  deeper  a\n\tmixed\n```\n    code\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_long_lines() {
+        assert_eq!(
+            long_lines("short\nthis is long\n```\nlong code\n```\n", 8),
+            vec![2]
+        );
+    }
+}
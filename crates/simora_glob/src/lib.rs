@@ -1,17 +1,33 @@
-use globset;
 use std::fmt;
-use std::path:: Path;
+use std::path::Path;
 use std::str::FromStr;
 
+/// How a [Glob] matches paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlobOptions {
+    /// Whether letters must match in case, `true` by default
+    pub case_sensitive: bool,
+}
+
+impl Default for GlobOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Glob {
     is_negated: bool,
+    /// The pattern as written, without the leading `!`
+    pattern: String,
     glob: globset::GlobMatcher,
 }
 
 impl PartialEq for Glob {
     fn eq(&self, other: &Self) -> bool {
-        self.is_negated == other.is_negated && self.glob.glob() == other.glob.glob()
+        self.is_negated == other.is_negated && self.pattern == other.pattern
     }
 }
 
@@ -20,9 +36,11 @@ pub enum GlobErrorKind {
     DanglingEscape,
     InvalidEscape,
     InvalidGlobStar,
-    UnsupportedAlternates,
-    UnsupportedCharacterClass,
-    UnsupportedAnyCharacter,
+    UnclosedAlternates,
+    UnopenedAlternates,
+    NestedAlternates,
+    UnclosedCharacterClass,
+    SeparatorInCharacterClass,
 }
 
 impl fmt::Display for GlobErrorKind {
@@ -33,17 +51,15 @@ impl fmt::Display for GlobErrorKind {
             Self::InvalidGlobStar => {
                 r"`**` must be enclosed by the path separator `/`, or the start/end of the glob and mustn't be followed by `/**`."
             }
-            Self::UnsupportedAlternates => {
-                r"Alternates `{}` are not supported. Use `\{` and `\}` to escape the characters."
-            }
-            Self::UnsupportedCharacterClass => {
-                r"Character class `[]` are not supported. Use `\[` and `\]` to escape the characters."
-            }
-            Self::UnsupportedAnyCharacter => {
-                r"`?` matcher is not supported. Use `\?` to escape the character."
+            Self::UnclosedAlternates => "Alternates `{` must be closed by `}`.",
+            Self::UnopenedAlternates => "`}` must close alternates opened by `{`.",
+            Self::NestedAlternates => "Alternates `{}` cannot be nested.",
+            Self::UnclosedCharacterClass => "Character class `[` must be closed by `]`.",
+            Self::SeparatorInCharacterClass => {
+                "Character class `[]` cannot match the path separator `/`."
             }
         };
-        write!(f, "Invalid glob pattern: {}", desc)
+        write!(f, "{}", desc)
     }
 }
 
@@ -57,6 +73,45 @@ impl GlobError {
     fn new(kind: GlobErrorKind, index: Option<u32>) -> Self {
         Self { kind, index }
     }
+
+    pub fn kind(&self) -> &GlobErrorKind {
+        &self.kind
+    }
+
+    /// Byte offset of the offending character in the pattern, if known
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    /// `pattern` with a caret under the offending character on the next line
+    pub fn highlight(&self, pattern: &str) -> Option<String> {
+        let index = (self.index? as usize).min(pattern.len());
+        let column = pattern.get(..index)?.chars().count();
+        Some(format!("{}\n{}^", pattern, " ".repeat(column)))
+    }
+
+    /// A corrected `pattern` and what the correction does, if one can be made
+    pub fn suggestion(&self, pattern: &str) -> Option<String> {
+        let index = self.index? as usize;
+        let character = pattern.get(index..)?.chars().next()?;
+        let (fixed, hint) = match self.kind {
+            GlobErrorKind::DanglingEscape => (
+                format!("{}\\", pattern),
+                "to match `\\` literally".to_string(),
+            ),
+            GlobErrorKind::InvalidGlobStar => (
+                format!("{}{}", &pattern[..index], &pattern[index + 1..]),
+                "to match within a single directory".to_string(),
+            ),
+            GlobErrorKind::SeparatorInCharacterClass => return None,
+            _ => (
+                format!("{}\\{}", &pattern[..index], &pattern[index..]),
+                format!("to match `{}` literally", character),
+            ),
+        };
+        Glob::from_str(&fixed).ok()?;
+        Some(format!("Use `{}` {}.", fixed, hint))
+    }
 }
 
 impl std::error::Error for GlobError {}
@@ -71,23 +126,39 @@ impl FromStr for Glob {
     type Err = GlobError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value, GlobOptions::default())
+    }
+}
+
+impl Glob {
+    /// Parses `value`, matching paths as set by `options`. Paths are matched from the
+    /// start, a leading `/` stating so explicitly.
+    pub fn parse(value: &str, options: GlobOptions) -> Result<Self, GlobError> {
         let (is_negated, value) = if let Some(stripped) = value.strip_prefix('!') {
             (true, stripped)
         } else {
             (false, value)
         };
-        validate_glob(value)?;
-        let mut glob_builder = globset::GlobBuilder::new(value);
+        let anchored = value.strip_prefix('/').unwrap_or(value);
+        // Report positions in the pattern as written
+        let offset = (is_negated as usize + value.len() - anchored.len()) as u32;
+        let translated = validate_glob(anchored).map_err(|error| GlobError {
+            index: error.index.map(|index| index + offset),
+            ..error
+        })?;
+        let mut glob_builder = globset::GlobBuilder::new(&translated);
         // Allow escaping with `\` on all platforms
         glob_builder.backslash_escape(true);
         // Only `**` can match `/`
         glob_builder.literal_separator(true);
+        glob_builder.case_insensitive(!options.case_sensitive);
         match glob_builder.build() {
             Ok(glob) => Ok(Glob {
                 is_negated,
+                pattern: value.to_string(),
                 glob: glob.compile_matcher(),
             }),
-            Err(error) => Err(GlobError::new(GlobErrorKind::InvalidGlobStar, None)),
+            Err(_) => Err(GlobError::new(GlobErrorKind::InvalidGlobStar, None)),
         }
     }
 }
@@ -95,7 +166,7 @@ impl FromStr for Glob {
 impl fmt::Display for Glob {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let negation = if self.is_negated { "!" } else { "" };
-        write!(f, "{}{}", negation, self.glob.glob())
+        write!(f, "{}{}", negation, self.pattern)
     }
 }
 
@@ -146,6 +217,46 @@ impl<'a> CandidatePath<'a> {
     }
 }
 
+/// A list of globs compiled to be matched in a single pass. As with
+/// [`CandidatePath::matches_with_exceptions`], the last matching glob decides, a negated
+/// one making an exception.
+#[derive(Debug, Clone)]
+pub struct GlobSet {
+    globs: Vec<Glob>,
+    set: globset::GlobSet,
+}
+
+impl GlobSet {
+    pub fn new(globs: Vec<Glob>) -> Self {
+        let mut builder = globset::GlobSetBuilder::new();
+        for glob in &globs {
+            builder.add(glob.glob.glob().clone());
+        }
+        Self {
+            // Every glob compiled on its own, so the set only fails past regex size limits
+            set: builder
+                .build()
+                .unwrap_or_else(|_| globset::GlobSet::empty()),
+            globs,
+        }
+    }
+
+    pub fn globs(&self) -> &[Glob] {
+        &self.globs
+    }
+
+    /// The index of the glob deciding whether `path` matches, the last one matching it
+    pub fn deciding_match(&self, path: &CandidatePath) -> Option<usize> {
+        self.set.matches_candidate(&path.0).into_iter().max()
+    }
+
+    /// Whether the deciding glob of `path` exists and is not negated
+    pub fn is_match(&self, path: &CandidatePath) -> bool {
+        self.deciding_match(path)
+            .is_some_and(|index| !self.globs[index].is_negated)
+    }
+}
+
 impl Glob {
     pub fn is_negated(&self) -> bool {
         self.is_negated
@@ -160,86 +271,169 @@ impl Glob {
     }
 }
 
-fn validate_glob(pattern: &str) -> Result<(), GlobError> {
-    let mut it = pattern.bytes().enumerate();
+/// Checks `pattern` and translates it for `globset`. As in `.gitignore`, `?`, `*` and
+/// character classes never match `/`.
+fn validate_glob(pattern: &str) -> Result<String, GlobError> {
+    let error = |kind, i: usize| GlobError::new(kind, Some(i as u32));
+    let bytes = pattern.as_bytes();
+    let mut translated = String::with_capacity(pattern.len());
+    // Start of the alternates being read, if any
+    let mut alternates: Option<usize> = None;
     let mut allow_globstar = true;
-    while let Some((i, c)) = it.next() {
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
         match c {
-            b'*' => {
-                let mut lookahead = it.clone();
-                if matches!(lookahead.next(), Some((_, b'*'))) {
-                    if !allow_globstar || !matches!(lookahead.next(), None | Some((_, b'/'))) {
-                        return Err(GlobError::new(
-                            GlobErrorKind::InvalidGlobStar,
-                            Some(i as u32),
-                        ));
-                    }
-                    // Eat `*`
-                    it.next();
-                    // Eat `/`
-                    it.next();
+            b'*' if bytes.get(i + 1) == Some(&b'*') => {
+                let end_of_segment = match bytes.get(i + 2) {
+                    None | Some(b'/') => true,
+                    Some(b',' | b'}') => alternates.is_some(),
+                    _ => false,
+                };
+                if !allow_globstar || !end_of_segment {
+                    return Err(error(GlobErrorKind::InvalidGlobStar, i));
                 }
+                translated.push_str("**");
+                i += 2;
+                // Eat `/`
+                if bytes.get(i) == Some(&b'/') {
+                    translated.push('/');
+                    i += 1;
+                }
+                allow_globstar = false;
+                continue;
             }
             b'\\' => {
                 // Accept a restrictive set of escape sequences
-                if let Some((_, c)) = it.next() {
-                    if !matches!(c, b'!' | b'*' | b'?' | b'{' | b'}' | b'[' | b']' | b'\\') {
-                        return Err(GlobError::new(GlobErrorKind::InvalidEscape, Some(i as u32)));
+                match bytes.get(i + 1) {
+                    Some(b'!' | b'*' | b'?' | b'{' | b'}' | b'[' | b']' | b',' | b'\\') => {
+                        translated.push_str(&pattern[i..i + 2]);
+                        i += 2;
+                        allow_globstar = false;
+                        continue;
                     }
-                } else {
-                    return Err(GlobError::new(
-                        GlobErrorKind::DanglingEscape,
-                        Some(i as u32),
-                    ));
+                    Some(_) => return Err(error(GlobErrorKind::InvalidEscape, i)),
+                    None => return Err(error(GlobErrorKind::DanglingEscape, i)),
                 }
             }
-            b'?' => {
-                return Err(GlobError::new(
-                    GlobErrorKind::UnsupportedAnyCharacter,
-                    Some(i as u32),
-                ));
+            b'[' => {
+                let end = character_class_end(bytes, i)
+                    .ok_or_else(|| error(GlobErrorKind::UnclosedCharacterClass, i))?;
+                translated.push_str(&translate_character_class(pattern, i, end)?);
+                i = end + 1;
+                allow_globstar = false;
+                continue;
             }
-            b'[' | b']' => {
-                return Err(GlobError::new(
-                    GlobErrorKind::UnsupportedCharacterClass,
-                    Some(i as u32),
-                ));
+            b'{' if alternates.is_some() => {
+                return Err(error(GlobErrorKind::NestedAlternates, i));
             }
-            b'{' | b'}' => {
-                return Err(GlobError::new(
-                    GlobErrorKind::UnsupportedAlternates,
-                    Some(i as u32),
-                ));
+            b'{' => alternates = Some(i),
+            b'}' if alternates.is_none() => {
+                return Err(error(GlobErrorKind::UnopenedAlternates, i));
             }
+            b'}' => alternates = None,
             _ => {}
         }
-        allow_globstar = c == b'/';
+        // Only push whole characters, `pattern` may hold multi-byte ones
+        let len = pattern[i..].chars().next().map_or(1, char::len_utf8);
+        translated.push_str(&pattern[i..i + len]);
+        allow_globstar = c == b'/' || (alternates.is_some() && matches!(c, b'{' | b','));
+        i += len;
+    }
+    if let Some(start) = alternates {
+        return Err(error(GlobErrorKind::UnclosedAlternates, start));
+    }
+    Ok(translated)
+}
+
+/// The index of the `]` closing the class opened at `start`. A `]` right after the
+/// opening `[`, `[!` or `[^` is a member of the class.
+fn character_class_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if matches!(bytes.get(i), Some(b'!' | b'^')) {
+        i += 1;
+    }
+    if bytes.get(i) == Some(&b']') {
+        i += 1;
+    }
+    (i..bytes.len()).find(|&i| bytes[i] == b']')
+}
+
+/// Makes negated classes exclude `/` and rejects classes that could match it
+fn translate_character_class(pattern: &str, start: usize, end: usize) -> Result<String, GlobError> {
+    let class = &pattern[start..=end];
+    let inner = &class[1..class.len() - 1];
+    let members = inner.strip_prefix(['!', '^']).unwrap_or(inner);
+    let negated = members.len() < inner.len();
+    if !negated {
+        let bytes = members.as_bytes();
+        let matches_separator = bytes.iter().enumerate().any(|(i, &c)| match c {
+            b'/' => true,
+            b'-' if i > 0 && i + 1 < bytes.len() => bytes[i - 1] <= b'/' && b'/' <= bytes[i + 1],
+            _ => false,
+        });
+        if matches_separator {
+            return Err(GlobError::new(
+                GlobErrorKind::SeparatorInCharacterClass,
+                Some(start as u32),
+            ));
+        }
+        return Ok(class.to_string());
     }
-    Ok(())
+    // Add `/` before a trailing `-`, which would otherwise start a range
+    let (members, dash) = match members.strip_suffix('-') {
+        Some(members) => (members, "-"),
+        None => (members, ""),
+    };
+    Ok(format!("{}{}/{}]", &class[..2], members, dash))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-    use std::path::Path;
     use std::str::FromStr;
-    #[cfg(test)]
-    use tempfile::TempDir;
 
     #[test]
     fn test_validate_glob() {
         assert!(matches!(
-            Glob::from_str("*.[jt]s"),
+            Glob::from_str("*.[jt"),
             Err(GlobError {
-                kind: GlobErrorKind::UnsupportedCharacterClass,
+                kind: GlobErrorKind::UnclosedCharacterClass,
+                index: Some(2),
+            })
+        ));
+        assert!(matches!(
+            Glob::from_str("a[/]b"),
+            Err(GlobError {
+                kind: GlobErrorKind::SeparatorInCharacterClass,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Glob::from_str("a[+-0]b"),
+            Err(GlobError {
+                kind: GlobErrorKind::SeparatorInCharacterClass,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Glob::from_str("docs/{drafts,archive"),
+            Err(GlobError {
+                kind: GlobErrorKind::UnclosedAlternates,
+                index: Some(5),
+            })
+        ));
+        assert!(matches!(
+            Glob::from_str("a}"),
+            Err(GlobError {
+                kind: GlobErrorKind::UnopenedAlternates,
                 ..
             })
         ));
         assert!(matches!(
-            Glob::from_str("?*.js"),
+            Glob::from_str("{a,{b,c}}"),
             Err(GlobError {
-                kind: GlobErrorKind::UnsupportedAnyCharacter,
+                kind: GlobErrorKind::NestedAlternates,
                 ..
             })
         ));
@@ -296,6 +490,64 @@ mod tests {
         assert!(Glob::from_str(r"/**/").is_ok());
         assert!(Glob::from_str(r"**/").is_ok());
         assert!(Glob::from_str(r"/**").is_ok());
+        assert!(Glob::from_str(r"\{a,b\}").is_ok());
+        assert!(Glob::from_str(r"\[a\]\?").is_ok());
+        assert!(Glob::from_str("{**/a,b/**}").is_ok());
+        assert!(Glob::from_str("[]]").is_ok());
+    }
+
+    #[test]
+    fn test_any_character() {
+        let glob = Glob::from_str("a?c").unwrap();
+        assert!(glob.is_match("abc"));
+        assert!(!glob.is_match("ac"));
+        assert!(!glob.is_match("a/c"));
+        assert!(Glob::from_str(r"a\?").unwrap().is_match("a?"));
+        assert!(!Glob::from_str(r"a\?").unwrap().is_match("ab"));
+    }
+
+    #[test]
+    fn test_character_classes() {
+        let glob = Glob::from_str("*.[mM][dD]").unwrap();
+        assert!(glob.is_match("README.md"));
+        assert!(glob.is_match("notes.MD"));
+        assert!(!glob.is_match("notes.mdx"));
+
+        let glob = Glob::from_str("v[0-9]").unwrap();
+        assert!(glob.is_match("v7"));
+        assert!(!glob.is_match("vx"));
+
+        // Negated classes never match `/`, as in `.gitignore`
+        for pattern in ["a[!b]c", "a[^b]c", "a[!b-]c", "a[!-]c"] {
+            let glob = Glob::from_str(pattern).unwrap();
+            assert!(glob.is_match("axc"), "{}", pattern);
+            assert!(!glob.is_match("a/c"), "{}", pattern);
+            assert_eq!(glob.to_string(), pattern);
+        }
+        assert!(!Glob::from_str("a[!b]c").unwrap().is_match("abc"));
+        assert!(!Glob::from_str("a[!b-]c").unwrap().is_match("a-c"));
+
+        assert!(Glob::from_str("[]]").unwrap().is_match("]"));
+        assert!(Glob::from_str(r"\[a\]").unwrap().is_match("[a]"));
+    }
+
+    #[test]
+    fn test_alternates() {
+        let glob = Glob::from_str("docs/{drafts,archive}/**").unwrap();
+        assert!(glob.is_match("docs/drafts/post.md"));
+        assert!(glob.is_match("docs/archive/2024/post.md"));
+        assert!(!glob.is_match("docs/published/post.md"));
+
+        let glob = Glob::from_str("*.{md,markdown}").unwrap();
+        assert!(glob.is_match("README.markdown"));
+        assert!(!glob.is_match("src/README.md"));
+
+        let glob = Glob::from_str("{**/vendor,build}/**").unwrap();
+        assert!(glob.is_match("a/b/vendor/x.md"));
+        assert!(glob.is_match("build/x.md"));
+
+        assert!(Glob::from_str(r"\{a,b\}").unwrap().is_match("{a,b}"));
+        assert!(Glob::from_str(r"{a\,b,c}").unwrap().is_match("a,b"));
     }
 
     #[test]
@@ -324,6 +576,82 @@ mod tests {
         ]));
     }
 
+    #[test]
+    fn test_glob_set() {
+        let set = GlobSet::new(
+            ["**/vendor/**", "docs/**", "!**/vendor/keep/**", "*.md"]
+                .iter()
+                .map(|pattern| Glob::from_str(pattern).unwrap())
+                .collect(),
+        );
+        let deciding = |path: &str| set.deciding_match(&CandidatePath::new(&path));
+        assert_eq!(deciding("a/vendor/b.md"), Some(0));
+        assert_eq!(deciding("docs/vendor/b.md"), Some(1));
+        assert_eq!(deciding("a/vendor/keep/b.md"), Some(2));
+        assert_eq!(deciding("README.md"), Some(3));
+        assert_eq!(deciding("src/lib.rs"), None);
+
+        assert!(set.is_match(&CandidatePath::new(&"a/vendor/b.md")));
+        assert!(!set.is_match(&CandidatePath::new(&"a/vendor/keep/b.md")));
+        assert!(!set.is_match(&CandidatePath::new(&"src/lib.rs")));
+
+        // Agrees with matching each glob in turn
+        for path in [
+            "a/vendor/b.md",
+            "a/vendor/keep/b.md",
+            "README.md",
+            "src/lib.rs",
+        ] {
+            let candidate = CandidatePath::new(&path);
+            assert_eq!(
+                set.is_match(&candidate),
+                candidate.matches_with_exceptions(set.globs())
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_highlight_and_suggestion() {
+        let error = Glob::from_str("!docs/{a").unwrap_err();
+        assert_eq!(error.index(), Some(6));
+        assert_eq!(error.highlight("!docs/{a").unwrap(), "!docs/{a\n      ^");
+        assert_eq!(
+            error.suggestion("!docs/{a").unwrap(),
+            r"Use `!docs/\{a` to match `{` literally."
+        );
+
+        let error = Glob::from_str("a/**b").unwrap_err();
+        assert_eq!(
+            error.suggestion("a/**b").unwrap(),
+            "Use `a/*b` to match within a single directory."
+        );
+        let error = Glob::from_str(r"a\").unwrap_err();
+        assert_eq!(
+            error.suggestion(r"a\").unwrap(),
+            r"Use `a\\` to match `\` literally."
+        );
+        let error = Glob::from_str("[a/]").unwrap_err();
+        assert_eq!(error.suggestion("[a/]"), None);
+    }
+
+    #[test]
+    fn test_anchored_and_case_insensitive() {
+        let glob = Glob::from_str("/docs/*.md").unwrap();
+        assert!(glob.is_match("docs/a.md"));
+        assert!(!glob.is_match("a/docs/a.md"));
+        assert_eq!(glob.to_string(), "/docs/*.md");
+        assert_eq!(Glob::from_str("!/docs/{a").unwrap_err().index(), Some(7));
+
+        assert!(!glob.is_match("Docs/A.MD"));
+        let options = GlobOptions {
+            case_sensitive: false,
+        };
+        let glob = Glob::parse("docs/*.md", options).unwrap();
+        assert!(glob.is_match("Docs/A.MD"));
+        let set = GlobSet::new(vec![glob]);
+        assert!(set.is_match(&CandidatePath::new(&"DOCS/readme.md")));
+    }
+
     #[test]
     fn test_to_string() {
         assert_eq!(Glob::from_str("**/*.rs").unwrap().to_string(), "**/*.rs");